@@ -0,0 +1,32 @@
+create table passkey_credential
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    credential_id   varchar(1400)       not null unique,
+    public_key      bytea               not null,
+    sign_count      bigint              not null default 0,
+    last_used_at    timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_passkey_credential_customer foreign key (customer_id) references customer (id)
+);
+create index index_passkey_credential_customer_id on passkey_credential (customer_id);
+
+create type passkey_ceremony as enum (
+    'registration',
+    'authentication'
+);
+
+create table passkey_challenge
+(
+    challenge       varchar(64)         not null,
+    ceremony        passkey_ceremony    not null,
+    customer_id     uuid,
+    expires_at      timestamptz         not null,
+    created_at      timestamptz         default now(),
+    primary key (challenge),
+
+    constraint fk_passkey_challenge_customer foreign key (customer_id) references customer (id)
+);
+create index index_passkey_challenge_expires_at on passkey_challenge (expires_at);
@@ -0,0 +1,15 @@
+create table customer_session
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    device_name     varchar(128),
+    user_agent      varchar(512),
+    ip_address      varchar(64)         not null,
+    last_seen_at    timestamptz         not null default now(),
+    revoked_at      timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_customer_session_customer foreign key (customer_id) references customer (id)
+);
+create index index_customer_session_customer_id on customer_session (customer_id);
@@ -0,0 +1,5 @@
+alter table customer add column name varchar(200);
+alter table customer add column updated_at timestamptz;
+
+-- Set when the token confirms a change to a new address instead of the current one.
+alter table email_verification_token add column email varchar(200);
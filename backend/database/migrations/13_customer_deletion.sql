@@ -0,0 +1,17 @@
+alter type customer_status add value 'deleted';
+alter table customer add column deleted_at timestamptz;
+
+-- Outlives the erasure as the record that it was asked for and when it was carried out.
+create table customer_deletion
+(
+    customer_id     uuid                not null,
+    requested_at    timestamptz         not null default now(),
+    scheduled_for   timestamptz         not null,
+    attempts        integer             not null default 0,
+    last_error      text,
+    completed_at    timestamptz,
+    primary key (customer_id),
+
+    constraint fk_customer_deletion_customer foreign key (customer_id) references customer (id)
+);
+create index index_customer_deletion_scheduled_for on customer_deletion (scheduled_for) where completed_at is null;
@@ -0,0 +1,32 @@
+create table biometrics_status_history
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    status          biometrics_status   not null,
+    changed_at      timestamptz         not null default now(),
+    primary key (id),
+
+    constraint fk_biometrics_status_history_customer foreign key (customer_id) references customer (id)
+);
+create index index_biometrics_status_history_customer_id on biometrics_status_history (customer_id);
+
+-- Biometrics stored before the history existed start it with their current status.
+insert into biometrics_status_history (id, customer_id, status, changed_at)
+select gen_random_uuid(), customer_id, status, coalesce(updated_at, created_at, now()) from biometrics;
+
+create type consent_purpose as enum (
+    'biometrics'
+);
+
+create table customer_consent
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    purpose         consent_purpose     not null,
+    granted_at      timestamptz         not null default now(),
+    revoked_at      timestamptz,
+    primary key (id),
+
+    constraint fk_customer_consent_customer foreign key (customer_id) references customer (id)
+);
+create index index_customer_consent_customer_id on customer_consent (customer_id);
@@ -0,0 +1,18 @@
+create type account_role as enum (
+    'customer',
+    'reviewer',
+    'admin'
+);
+
+create table customer_role
+(
+    customer_id     uuid                not null,
+    role            account_role        not null,
+    granted_at      timestamptz         not null default now(),
+    primary key (customer_id, role),
+
+    constraint fk_customer_role_customer foreign key (customer_id) references customer (id)
+);
+
+insert into customer_role (customer_id, role)
+select id, 'customer' from customer;
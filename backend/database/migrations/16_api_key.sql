@@ -0,0 +1,18 @@
+create type api_key_scope as enum (
+    'biometrics_read',
+    'biometrics_review'
+);
+
+create table api_key
+(
+    id              uuid                not null,
+    name            varchar(100)        not null,
+    prefix          varchar(16)         not null unique,
+    key_hash        varchar(64)         not null unique,
+    scopes          api_key_scope[]     not null,
+    expires_at      timestamptz,
+    last_used_at    timestamptz,
+    revoked_at      timestamptz,
+    created_at      timestamptz         not null default now(),
+    primary key (id)
+);
@@ -0,0 +1,56 @@
+create type oauth_grant_type as enum (
+    'authorization_code',
+    'refresh_token',
+    'client_credentials'
+);
+
+create table oauth_client
+(
+    id              uuid                not null,
+    name            varchar(100)        not null,
+    -- Public clients, like single page and mobile apps, have no secret and rely on PKCE alone.
+    secret_hash     varchar(64),
+    redirect_uris   text[]              not null,
+    grant_types     oauth_grant_type[]  not null,
+    scopes          text[]              not null,
+    revoked_at      timestamptz,
+    created_at      timestamptz         not null default now(),
+    primary key (id)
+);
+
+create table oauth_authorization_code
+(
+    id              uuid                not null,
+    code_hash       varchar(64)         not null unique,
+    client_id       uuid                not null,
+    customer_id     uuid                not null,
+    redirect_uri    text                not null,
+    scope           text                not null,
+    code_challenge  varchar(128)        not null,
+    nonce           varchar(255),
+    -- Session started by the exchange, revoked when the code shows up again.
+    session_id      uuid,
+    expires_at      timestamptz         not null,
+    consumed_at     timestamptz,
+    created_at      timestamptz         not null default now(),
+    primary key (id),
+
+    constraint fk_oauth_authorization_code_client foreign key (client_id) references oauth_client (id),
+    constraint fk_oauth_authorization_code_customer foreign key (customer_id) references customer (id)
+);
+
+-- Ties a customer session, and so its refresh token family, to the client it was granted to.
+create table oauth_grant
+(
+    session_id      uuid                not null,
+    client_id       uuid                not null,
+    customer_id     uuid                not null,
+    scope           text                not null,
+    created_at      timestamptz         not null default now(),
+    primary key (session_id),
+
+    constraint fk_oauth_grant_session foreign key (session_id) references customer_session (id),
+    constraint fk_oauth_grant_client foreign key (client_id) references oauth_client (id),
+    constraint fk_oauth_grant_customer foreign key (customer_id) references customer (id)
+);
+create index index_oauth_grant_client_id on oauth_grant (client_id);
@@ -0,0 +1,11 @@
+create table revoked_client_token
+(
+    jti             uuid                not null,
+    client_id       uuid                not null,
+    expires_at      timestamptz         not null,
+    created_at      timestamptz         default now(),
+    primary key (jti),
+
+    constraint fk_revoked_client_token_client foreign key (client_id) references oauth_client (id)
+);
+create index index_revoked_client_token_expires_at on revoked_client_token (expires_at);
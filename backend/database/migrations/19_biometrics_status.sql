@@ -0,0 +1,3 @@
+-- A missing comma in 1_initial merged 'take_again' and 'conclued' into a single value.
+alter type biometrics_status rename value 'take_againconclued' to 'take_again';
+alter type biometrics_status add value 'conclued';
@@ -0,0 +1,30 @@
+-- Emails are looked up lowercased since the email policy, so the stored ones are lowercased too.
+-- Accounts whose emails only differ in case keep the oldest one on the address, the others are parked
+-- on an address of their own and their original email is kept here until support sorts them out.
+create table customer_email_conflict
+(
+    customer_id     uuid                not null,
+    email           varchar(200)        not null,
+    created_at      timestamptz         default now(),
+    primary key (customer_id),
+
+    constraint fk_customer_email_conflict_customer foreign key (customer_id) references customer (id)
+);
+
+insert into customer_email_conflict (customer_id, email)
+select id, email
+from (
+    select id, email, row_number() over (partition by lower(trim(email)) order by created_at nulls last, id) as position
+    from customer
+) ranked
+where position > 1;
+
+update customer
+set email = id || '@email-conflict.invalid'
+where id in (select customer_id from customer_email_conflict);
+
+update customer
+set email = lower(trim(email))
+where email <> lower(trim(email));
+
+create unique index index_customer_email_lower on customer (lower(email));
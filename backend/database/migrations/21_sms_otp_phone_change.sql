@@ -0,0 +1,2 @@
+-- Set when the code confirms a change to a new phone number instead of signing in.
+alter table sms_otp add column phone_number varchar(16);
@@ -0,0 +1,2 @@
+-- Magic links are not stored, so the resend window is kept on the customer.
+alter table customer add column magic_link_sent_at timestamptz;
@@ -0,0 +1,15 @@
+create table refresh_token
+(
+    id              uuid                not null,
+    family_id       uuid                not null,
+    customer_id     uuid                not null,
+    token_hash      varchar(64)         not null unique,
+    expires_at      timestamptz         not null,
+    rotated_at      timestamptz,
+    revoked_at      timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_refresh_token_customer foreign key (customer_id) references customer (id)
+);
+create index index_refresh_token_family_id on refresh_token (family_id);
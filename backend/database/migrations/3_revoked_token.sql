@@ -0,0 +1,13 @@
+create table revoked_token
+(
+    jti             uuid                not null,
+    customer_id     uuid                not null,
+    expires_at      timestamptz         not null,
+    created_at      timestamptz         default now(),
+    primary key (jti),
+
+    constraint fk_revoked_token_customer foreign key (customer_id) references customer (id)
+);
+create index index_revoked_token_expires_at on revoked_token (expires_at);
+
+alter table customer add column tokens_revoked_at timestamptz;
@@ -0,0 +1,22 @@
+create type customer_status as enum (
+    'pending_verification',
+    'active'
+);
+
+-- Customers created before the verification flow keep working as they did.
+alter table customer add column status customer_status not null default 'active';
+alter table customer add column email_verified_at timestamptz;
+
+create table email_verification_token
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    token_hash      varchar(64)         not null unique,
+    expires_at      timestamptz         not null,
+    used_at         timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_email_verification_token_customer foreign key (customer_id) references customer (id)
+);
+create index index_email_verification_token_customer_id on email_verification_token (customer_id);
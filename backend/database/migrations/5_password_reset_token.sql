@@ -0,0 +1,13 @@
+create table password_reset_token
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    token_hash      varchar(64)         not null unique,
+    expires_at      timestamptz         not null,
+    used_at         timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_password_reset_token_customer foreign key (customer_id) references customer (id)
+);
+create index index_password_reset_token_customer_id on password_reset_token (customer_id);
@@ -0,0 +1,14 @@
+create type sign_in_attempt_scope as enum (
+    'email',
+    'ip'
+);
+
+create table sign_in_attempt
+(
+    scope           sign_in_attempt_scope   not null,
+    identifier      varchar(200)            not null,
+    failures        integer                 not null default 0,
+    last_failure_at timestamptz             not null,
+    locked_until    timestamptz,
+    primary key (scope, identifier)
+);
@@ -0,0 +1,24 @@
+create table customer_mfa
+(
+    customer_id         uuid                not null,
+    secret_encrypted    text                not null,
+    last_used_step      bigint,
+    enabled_at          timestamptz,
+    created_at          timestamptz         default now(),
+    primary key (customer_id),
+
+    constraint fk_customer_mfa_customer foreign key (customer_id) references customer (id)
+);
+
+create table mfa_recovery_code
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    code_hash       varchar(64)         not null,
+    used_at         timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_mfa_recovery_code_customer foreign key (customer_id) references customer (id)
+);
+create index index_mfa_recovery_code_customer_id on mfa_recovery_code (customer_id);
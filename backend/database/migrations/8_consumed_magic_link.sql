@@ -0,0 +1,11 @@
+create table consumed_magic_link
+(
+    jti             uuid                not null,
+    customer_id     uuid                not null,
+    expires_at      timestamptz         not null,
+    consumed_at     timestamptz         default now(),
+    primary key (jti),
+
+    constraint fk_consumed_magic_link_customer foreign key (customer_id) references customer (id)
+);
+create index index_consumed_magic_link_expires_at on consumed_magic_link (expires_at);
@@ -0,0 +1,16 @@
+alter table customer add column phone_number varchar(16) unique;
+
+create table sms_otp
+(
+    id              uuid                not null,
+    customer_id     uuid                not null,
+    code_hash       varchar(64)         not null,
+    attempts        integer             not null default 0,
+    expires_at      timestamptz         not null,
+    used_at         timestamptz,
+    created_at      timestamptz         default now(),
+    primary key (id),
+
+    constraint fk_sms_otp_customer foreign key (customer_id) references customer (id)
+);
+create index index_sms_otp_customer_id on sms_otp (customer_id);
@@ -13,7 +13,11 @@ impl AppRoutes {
     pub async fn routes(app_state: AppState) -> Result<Router, Box<dyn std::error::Error>> {
         let app = Router::new()
             .nest("/auth", AppRoutes::auth_routes())
-            .nest("/biometrics", AppRoutes::biometrics_routes())
+            .nest("/biometrics", AppRoutes::biometrics_routes(&app_state))
+            .nest("/customers", AppRoutes::customers_routes(&app_state))
+            .nest("/admin", AppRoutes::admin_routes(&app_state))
+            .nest("/oauth", AppRoutes::oauth_routes())
+            .nest("/.well-known", AppRoutes::well_known_routes())
             .with_state(app_state)
             .layer(CatchPanicLayer::new())
             .layer(from_fn(support::axum::log_response))
@@ -1,12 +1,14 @@
 use std::env;
 use std::net::SocketAddr;
 use login_auth_service::app::AppRoutes;
+use login_auth_service::feature::customers::deletion::DeletionWorker;
 use login_auth_service::state::AppState;
 use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let app_state = AppState::create().await?;
+    DeletionWorker::spawn(app_state.clone());
     let app = AppRoutes::routes(app_state).await?;
     info!("starting...");
     let port = env::var("PORT")
@@ -15,7 +17,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or(9095);
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     info!("Listening on {}", addr);
     Ok(())
 }
@@ -0,0 +1,86 @@
+use axum::extract::{Path, State};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use uuid::Uuid;
+
+use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::{AppError, AppErrorData, FieldErrors}}, state::AppState};
+
+use super::{authorization::AuthorizedAdmin, domain::{ApiKey, ApiKeyDtoResponse, CreateApiKeyDtoRequest, CreatedApiKeyDtoResponse}};
+
+const NAME_MAX_LENGTH: usize = 100;
+
+pub struct ApiKeysUseCase;
+impl ApiKeysUseCase {
+    pub async fn create(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        AppJsonRequest(request): AppJsonRequest<CreateApiKeyDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<CreatedApiKeyDtoResponse>), AppError> {
+        let name = request.name.trim().to_string();
+        let mut errors = FieldErrors::new();
+        if name.is_empty() || name.chars().count() > NAME_MAX_LENGTH {
+            errors.insert(String::from("name"), vec![format!("must have between 1 and {} characters", NAME_MAX_LENGTH)]);
+        }
+        if request.scopes.is_empty() {
+            errors.insert(String::from("scopes"), vec![String::from("at least one scope is expected")]);
+        }
+        let expires_at = match request.expires_at.as_deref().map(DateTime::parse_from_rfc3339) {
+            None => None,
+            Some(Ok(expires_at)) if expires_at > Utc::now() => Some(expires_at.with_timezone(&Utc)),
+            Some(_) => {
+                errors.insert(String::from("expiresAt"), vec![String::from("must be a RFC 3339 timestamp in the future")]);
+                None
+            }
+        };
+        if !errors.is_empty() {
+            let fields = errors.keys().cloned().collect::<Vec<String>>().join(", ");
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                format!("invalid fields: {}", fields),
+                None,
+            )
+            .with_errors(errors)
+            .to_business_error());
+        }
+        let (api_key, plain_key) = ApiKey::new(name, request.scopes, expires_at);
+        let mut transaction = app_state.begin_transaction().await?;
+        let api_key = ApiKey::insert(&mut transaction, api_key).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok((
+            StatusCode::CREATED,
+            AppJsonResponse::new(CreatedApiKeyDtoResponse {
+                key: plain_key,
+                api_key: ApiKeyDtoResponse::from(api_key),
+            })
+        ))
+    }
+
+    pub async fn list(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+    ) -> Result<AppJsonResponse<Vec<ApiKeyDtoResponse>>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let api_keys = ApiKey::find_all(&mut transaction).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(api_keys.into_iter().map(ApiKeyDtoResponse::from).collect()))
+    }
+
+    pub async fn revoke(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        Path(api_key_id): Path<Uuid>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let revoked = ApiKey::revoke(&mut transaction, api_key_id).await?;
+        app_state.commit_transaction(transaction).await?;
+        if !revoked {
+            return Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("API key not found"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use hyper::StatusCode;
+
+use crate::{feature::auth::{authorization::AuthorizedCustomer, domain::Role}, infra::errors::{AppError, AppErrorData}, state::AppState};
+
+/// The access token of an account holding the admin role, so every admin call is tied to an account.
+pub struct AuthorizedAdmin;
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthorizedAdmin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, app_state: &AppState) -> Result<Self, Self::Rejection> {
+        let AuthorizedCustomer(claims) = AuthorizedCustomer::from_request_parts(parts, app_state).await?;
+        if !claims.has_role(Role::Admin) {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                String::from("Missing required role"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(AuthorizedAdmin)
+    }
+}
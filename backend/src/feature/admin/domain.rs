@@ -0,0 +1,203 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::{PgHasArrayType, PgTypeInfo}, prelude::FromRow, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{infra::{errors::{AppError, AppErrorData, ToBusinessError}, observability::Tags}, support::{hash::hash_sha256::HashSha256, random::RandomToken}};
+
+const API_KEY_PREFIX: &str = "lsk_";
+const API_KEY_ID_LENGTH: usize = 8;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyDtoRequest {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// RFC 3339 timestamp, keys without one never expire.
+    pub expires_at: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyDtoResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String
+}
+impl ApiKeyDtoResponse {
+    pub fn from(domain: ApiKey) -> Self {
+        Self {
+            id: domain.id,
+            name: domain.name,
+            prefix: domain.prefix,
+            scopes: domain.scopes,
+            expires_at: domain.expires_at.map(format_timestamp),
+            last_used_at: domain.last_used_at.map(format_timestamp),
+            revoked_at: domain.revoked_at.map(format_timestamp),
+            created_at: format_timestamp(domain.created_at),
+        }
+    }
+}
+
+/// The only time the plain key is shown, it can not be recovered afterwards.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKeyDtoResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyDtoResponse
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "api_key_scope", rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Reads the biometrics of any customer.
+    BiometricsRead,
+    /// Changes the status of any customer biometrics, like a reviewer does.
+    BiometricsReview,
+}
+
+impl PgHasArrayType for ApiKeyScope {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_api_key_scope")
+    }
+}
+
+/// Credential of internal callers such as the backoffice and batch jobs, presented in `X-Api-Key`.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Start of the key, kept in plain so a key can be recognised in the list.
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl ApiKey {
+    /// Returns the key to store together with the plain key handed to the caller,
+    /// which is never persisted.
+    pub fn new(name: String, scopes: Vec<ApiKeyScope>, expires_at: Option<DateTime<Utc>>) -> (Self, String) {
+        let id: String = RandomToken::generate()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(API_KEY_ID_LENGTH)
+            .collect();
+        let prefix = format!("{}{}", API_KEY_PREFIX, id);
+        let plain_key = format!("{}_{}", prefix, RandomToken::generate());
+        let api_key = Self {
+            id: Uuid::now_v7(),
+            name,
+            prefix,
+            key_hash: HashSha256::encode(&plain_key),
+            scopes,
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+        };
+        (api_key, plain_key)
+    }
+
+    /// Attributes the request to the key in the logs.
+    pub fn tags(&self) -> Tags {
+        Tags {
+            user_id: None,
+            api_key_id: Some(self.id.to_string()),
+        }
+    }
+
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn ensure_scope(&self, scope: ApiKeyScope) -> Result<(), AppError> {
+        if !self.has_scope(scope) {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                String::from("API key is missing the required scope"),
+                Some(self.tags()),
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, api_key: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO api_key
+                (id, name, prefix, key_hash, scopes, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(api_key.id)
+            .bind(api_key.name)
+            .bind(api_key.prefix)
+            .bind(api_key.key_hash)
+            .bind(api_key.scopes)
+            .bind(api_key.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert api key", None))
+    }
+
+    pub async fn find_all(transaction: &mut Transaction<'_, Postgres>) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM api_key
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as(query)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find api keys", None))
+    }
+
+    /// Only keys neither revoked nor expired are found, and finding one records its use.
+    pub async fn find_active(transaction: &mut Transaction<'_, Postgres>, plain_key: &str) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            UPDATE api_key
+            SET last_used_at = now()
+            WHERE key_hash = $1
+                AND revoked_at IS NULL
+                AND (expires_at IS NULL OR expires_at > now())
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(HashSha256::encode(plain_key))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find active api key", None))
+    }
+
+    /// Returns whether there was a key left to revoke.
+    pub async fn revoke(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE api_key
+            SET revoked_at = now()
+            WHERE id = $1
+                AND revoked_at IS NULL
+        "#;
+        let result = sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke api key", None))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
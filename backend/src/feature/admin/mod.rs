@@ -0,0 +1,6 @@
+pub mod api_keys;
+pub mod authorization;
+pub mod domain;
+pub mod oauth_clients;
+pub mod roles;
+pub mod unlock;
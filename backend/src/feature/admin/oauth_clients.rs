@@ -0,0 +1,103 @@
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{feature::oauth::domain::{CreateOauthClientDtoRequest, CreatedOauthClientDtoResponse, OauthClient, OauthClientDtoResponse, OauthGrantType, SUPPORTED_SCOPES}, infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::{AppError, AppErrorData, FieldErrors}}, state::AppState};
+
+use super::authorization::AuthorizedAdmin;
+
+const NAME_MAX_LENGTH: usize = 100;
+
+pub struct OauthClientsUseCase;
+impl OauthClientsUseCase {
+    pub async fn create(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        AppJsonRequest(request): AppJsonRequest<CreateOauthClientDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<CreatedOauthClientDtoResponse>), AppError> {
+        let name = request.name.trim().to_string();
+        let mut errors = FieldErrors::new();
+        if name.is_empty() || name.chars().count() > NAME_MAX_LENGTH {
+            errors.insert(String::from("name"), vec![format!("must have between 1 and {} characters", NAME_MAX_LENGTH)]);
+        }
+        if request.grant_types.is_empty() {
+            errors.insert(String::from("grantTypes"), vec![String::from("at least one grant type is expected")]);
+        } else if request.public && request.grant_types.contains(&OauthGrantType::ClientCredentials) {
+            errors.insert(String::from("grantTypes"), vec![String::from("public clients can not use client_credentials")]);
+        }
+        let uses_redirect = request.grant_types.contains(&OauthGrantType::AuthorizationCode);
+        if uses_redirect && request.redirect_uris.is_empty() {
+            errors.insert(String::from("redirectUris"), vec![String::from("at least one redirect URI is expected")]);
+        }
+        let invalid_redirect_uris = request
+            .redirect_uris
+            .iter()
+            .filter(|redirect_uri| !Url::parse(redirect_uri).is_ok_and(|url| url.fragment().is_none()))
+            .map(|redirect_uri| format!("{} is not an absolute URL without fragment", redirect_uri))
+            .collect::<Vec<String>>();
+        if !invalid_redirect_uris.is_empty() {
+            errors.insert(String::from("redirectUris"), invalid_redirect_uris);
+        }
+        let unsupported_scopes = request
+            .scopes
+            .iter()
+            .filter(|scope| !SUPPORTED_SCOPES.contains(&scope.as_str()))
+            .map(|scope| format!("{} is not supported", scope))
+            .collect::<Vec<String>>();
+        if !unsupported_scopes.is_empty() {
+            errors.insert(String::from("scopes"), unsupported_scopes);
+        }
+        if !errors.is_empty() {
+            let fields = errors.keys().cloned().collect::<Vec<String>>().join(", ");
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                format!("invalid fields: {}", fields),
+                None,
+            )
+            .with_errors(errors)
+            .to_business_error());
+        }
+        let (client, plain_secret) = OauthClient::new(name, request.redirect_uris, request.grant_types, request.scopes, request.public);
+        let mut transaction = app_state.begin_transaction().await?;
+        let client = OauthClient::insert(&mut transaction, client).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok((
+            StatusCode::CREATED,
+            AppJsonResponse::new(CreatedOauthClientDtoResponse {
+                client_secret: plain_secret,
+                client: OauthClientDtoResponse::from(client),
+            })
+        ))
+    }
+
+    pub async fn list(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+    ) -> Result<AppJsonResponse<Vec<OauthClientDtoResponse>>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let clients = OauthClient::find_all(&mut transaction).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(clients.into_iter().map(OauthClientDtoResponse::from).collect()))
+    }
+
+    /// Customers signed in through the client are signed out of it as well.
+    pub async fn revoke(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        Path(client_id): Path<Uuid>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let revoked = OauthClient::revoke(&mut transaction, client_id).await?;
+        app_state.commit_transaction(transaction).await?;
+        if !revoked {
+            return Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("OAuth client not found"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
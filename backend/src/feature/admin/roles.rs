@@ -0,0 +1,55 @@
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{feature::auth::domain::{Customer, CustomerRole, Role}, infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::{AppError, AppErrorData, FieldErrors}}, state::AppState};
+
+use super::authorization::AuthorizedAdmin;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerRolesDtoRequest {
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerRolesDtoResponse {
+    pub customer_id: Uuid,
+    pub roles: Vec<Role>,
+}
+
+pub struct RolesUseCase;
+impl RolesUseCase {
+    /// Replaces every role of the customer. The customer sees the change once the access
+    /// token is refreshed.
+    pub async fn update(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        Path(customer_id): Path<Uuid>,
+        AppJsonRequest(request): AppJsonRequest<CustomerRolesDtoRequest>,
+    ) -> Result<AppJsonResponse<CustomerRolesDtoResponse>, AppError> {
+        if request.roles.is_empty() {
+            let errors = FieldErrors::from([(
+                String::from("roles"),
+                vec![String::from("at least one role is expected")],
+            )]);
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("invalid fields: roles"),
+                None,
+            )
+            .with_errors(errors)
+            .to_business_error());
+        }
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, customer_id).await?;
+        let roles = CustomerRole::replace_all(&mut transaction, customer.id, &request.roles).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(CustomerRolesDtoResponse {
+            customer_id: customer.id,
+            roles,
+        }))
+    }
+}
@@ -0,0 +1,23 @@
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use uuid::Uuid;
+
+use crate::{feature::auth::domain::{Customer, SignInAttempt}, infra::errors::AppError, state::AppState};
+
+use super::authorization::AuthorizedAdmin;
+
+pub struct UnlockUseCase;
+impl UnlockUseCase {
+    /// Clears the failed sign in attempts of the customer's email. IP lockouts are left to expire.
+    pub async fn unlock(
+        State(app_state): State<AppState>,
+        _admin: AuthorizedAdmin,
+        Path(customer_id): Path<Uuid>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, customer_id).await?;
+        SignInAttempt::reset(&mut transaction, &customer.email).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
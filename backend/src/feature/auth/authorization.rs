@@ -0,0 +1,207 @@
+use axum::{async_trait, extract::{FromRequestParts, Request, State}, http::request::Parts, middleware::Next, response::Response};
+use hyper::{header::AUTHORIZATION, StatusCode};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{feature::admin::domain::{ApiKey, ApiKeyScope}, infra::{errors::{AppError, AppErrorData}, observability::Tags}, state::AppState, support::jwt::{AuthorizationClaims, Jwt}};
+
+use super::domain::{CustomerSession, RevokedToken, Role};
+
+const BEARER_PREFIX: &str = "Bearer ";
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Customer holding a first-party access token. Tokens issued to an OAuth client are refused,
+/// they are only accepted by the OAuth routes taking `ScopedCustomer`.
+pub struct AuthorizedCustomer(pub AuthorizationClaims);
+impl AuthorizedCustomer {
+    /// Validates the JWT signature and expiration and makes sure neither the token nor its
+    /// session was revoked since it was issued.
+    pub async fn verify(app_state: &AppState, jwt: String) -> Result<AuthorizationClaims, AppError> {
+        let claims = AuthorizationClaims::extract_jwt(jwt, &app_state.jwt_key_ring)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let revoked = Self::is_revoked(&mut transaction, &claims).await?;
+        app_state.commit_transaction(transaction).await?;
+        if revoked {
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Token revoked"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(claims)
+    }
+
+    /// Either the token itself or the session it was issued for was revoked.
+    pub async fn is_revoked(transaction: &mut Transaction<'_, Postgres>, claims: &AuthorizationClaims) -> Result<bool, AppError> {
+        Ok(RevokedToken::is_revoked(transaction, claims).await?
+            || CustomerSession::is_revoked(transaction, claims.sid).await?)
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthorizedCustomer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, app_state: &AppState) -> Result<Self, Self::Rejection> {
+        // Already verified by `RequireRole` when the route group declares a role.
+        if let Some(claims) = parts.extensions.get::<AuthorizationClaims>() {
+            return Ok(AuthorizedCustomer(claims.clone()));
+        }
+        let jwt = bearer_token(parts)?;
+        let claims = AuthorizedCustomer::verify(app_state, jwt).await?;
+        if claims.client_id.is_some() {
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Token issued to an OAuth client"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(AuthorizedCustomer(claims))
+    }
+}
+
+/// Customer holding either a first-party access token or one issued to an OAuth client, for
+/// the routes that check the scope of the token themselves.
+pub struct ScopedCustomer(pub AuthorizationClaims);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ScopedCustomer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, app_state: &AppState) -> Result<Self, Self::Rejection> {
+        let jwt = bearer_token(parts)?;
+        let claims = AuthorizedCustomer::verify(app_state, jwt).await?;
+        Ok(ScopedCustomer(claims))
+    }
+}
+
+/// Either a customer access token or an API key sent in `X-Api-Key`, for routes that also serve
+/// internal callers. The key is looked up whenever the header is present, the bearer token is ignored then.
+pub enum AuthorizedCaller {
+    Customer(AuthorizationClaims),
+    ApiKey(ApiKey),
+}
+impl AuthorizedCaller {
+    pub fn customer_id(&self) -> Option<Uuid> {
+        match self {
+            AuthorizedCaller::Customer(claims) => Some(claims.sub),
+            AuthorizedCaller::ApiKey(_) => None,
+        }
+    }
+
+    /// Keys with the `biometrics_review` scope act on biometrics like a reviewer does.
+    pub fn is_reviewer(&self) -> bool {
+        match self {
+            AuthorizedCaller::Customer(claims) => claims.has_role(Role::Reviewer),
+            AuthorizedCaller::ApiKey(api_key) => api_key.has_scope(ApiKeyScope::BiometricsReview),
+        }
+    }
+
+    pub fn tags(&self) -> Tags {
+        match self {
+            AuthorizedCaller::Customer(claims) => Tags {
+                user_id: Some(claims.sub.to_string()),
+                api_key_id: None,
+            },
+            AuthorizedCaller::ApiKey(api_key) => api_key.tags(),
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthorizedCaller {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, app_state: &AppState) -> Result<Self, Self::Rejection> {
+        let Some(plain_key) = api_key_header(parts) else {
+            let AuthorizedCustomer(claims) = AuthorizedCustomer::from_request_parts(parts, app_state).await?;
+            return Ok(AuthorizedCaller::Customer(claims));
+        };
+        let mut transaction = app_state.begin_transaction().await?;
+        let api_key = ApiKey::find_active(&mut transaction, &plain_key).await?;
+        app_state.commit_transaction(transaction).await?;
+        let api_key = api_key.ok_or_else(|| {
+            AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Invalid API key"),
+                None,
+            )
+            .to_business_error()
+        })?;
+        Ok(AuthorizedCaller::ApiKey(api_key))
+    }
+}
+
+/// Guards a route group, declared with
+/// `.route_layer(from_fn_with_state(RequireRole::any_of(app_state, &[Role::Customer]), RequireRole::check))`.
+/// Routes added after the layer are left open.
+#[derive(Clone)]
+pub struct RequireRole {
+    app_state: AppState,
+    roles: &'static [Role],
+    allow_api_keys: bool,
+}
+impl RequireRole {
+    pub fn any_of(app_state: &AppState, roles: &'static [Role]) -> Self {
+        Self {
+            app_state: app_state.clone(),
+            roles,
+            allow_api_keys: false,
+        }
+    }
+
+    /// Lets requests carrying `X-Api-Key` through, their handlers take `AuthorizedCaller`
+    /// and check the key scopes themselves.
+    pub fn or_api_key(mut self) -> Self {
+        self.allow_api_keys = true;
+        self
+    }
+
+    pub async fn check(State(guard): State<Self>, request: Request, next: Next) -> Result<Response, AppError> {
+        let (mut parts, body) = request.into_parts();
+        if guard.allow_api_keys && api_key_header(&parts).is_some() {
+            return Ok(next.run(Request::from_parts(parts, body)).await);
+        }
+        let AuthorizedCustomer(claims) = AuthorizedCustomer::from_request_parts(&mut parts, &guard.app_state).await?;
+        if !guard.roles.iter().any(|role| claims.has_role(*role)) {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                String::from("Missing required role"),
+                None,
+            )
+            .to_business_error());
+        }
+        parts.extensions.insert(claims);
+        Ok(next.run(Request::from_parts(parts, body)).await)
+    }
+}
+
+fn api_key_header(parts: &Parts) -> Option<String> {
+    parts
+        .headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn bearer_token(parts: &Parts) -> Result<String, AppError> {
+    let header = parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Authorization header is expected"),
+                None,
+            )
+            .to_business_error()
+        })?;
+    let jwt = header.strip_prefix(BEARER_PREFIX).unwrap_or(header);
+    Ok(jwt.trim().to_string())
+}
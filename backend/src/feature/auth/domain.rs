@@ -1,10 +1,12 @@
-use chrono::{DateTime, Utc};
-use hyper::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use hyper::{header::{HeaderValue, RETRY_AFTER}, StatusCode};
 use serde::{Deserialize, Serialize};
 use sqlx::{prelude::FromRow, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::{infra::errors::{AppError, AppErrorData, ToBusinessError}, support::hash::hash_bcrypt::HashBCrypt};
+use crate::{infra::{axum::ClientDevice, env::Environment, errors::{AppError, AppErrorData, ToBusinessError}}, support::{hash::{hash_aes_gcm::HashAesGcm, hash_sha256::HashSha256}, jwt::{MagicLinkClaims, RevocableClaims}, random::{RandomCode, RandomToken}, totp::Totp}};
+
+use super::policy::LockoutPolicy;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,41 +22,322 @@ pub struct CustomerDtoRequest {
     pub password: String
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenDtoRequest {
+    pub refresh_token: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutDtoRequest {
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub everywhere: bool
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDtoResponse {
+    pub id: Uuid,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+    pub created_at: String,
+    pub last_seen_at: String,
+    pub current: bool
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailDtoRequest {
+    pub token: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendVerificationDtoRequest {
+    pub email: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordDtoRequest {
+    pub email: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordDtoRequest {
+    pub token: String,
+    pub password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MagicLinkDtoRequest {
+    pub email: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumeMagicLinkDtoRequest {
+    pub token: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhoneNumberDtoRequest {
+    pub phone_number: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePhoneNumberDtoRequest {
+    pub phone_number: String,
+    pub current_password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPhoneNumberDtoRequest {
+    pub code: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifySmsOtpDtoRequest {
+    pub phone_number: String,
+    pub code: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRelyingPartyDto {
+    pub id: String,
+    pub name: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUserDto {
+    pub id: String,
+    pub name: String,
+    pub display_name: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCredentialParameterDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub alg: i64
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCredentialDescriptorDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub id: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticatorSelectionDto {
+    pub resident_key: String,
+    pub user_verification: String
+}
+
+/// Matches `PublicKeyCredentialCreationOptions`, binary fields are base64url encoded.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegistrationOptionsDtoResponse {
+    pub challenge: String,
+    pub rp: PasskeyRelyingPartyDto,
+    pub user: PasskeyUserDto,
+    pub pub_key_cred_params: Vec<PasskeyCredentialParameterDto>,
+    pub timeout: i64,
+    pub attestation: String,
+    pub authenticator_selection: PasskeyAuthenticatorSelectionDto,
+    pub exclude_credentials: Vec<PasskeyCredentialDescriptorDto>
+}
+
+/// Matches `PublicKeyCredentialRequestOptions`. No credentials are listed, so the
+/// authenticator offers the discoverable ones and nothing tells which emails have passkeys.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticationOptionsDtoResponse {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: i64,
+    pub user_verification: String,
+    pub allow_credentials: Vec<PasskeyCredentialDescriptorDto>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PasskeyAttestationDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "attestationObject")]
+    pub attestation_object: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegistrationDtoRequest {
+    pub id: String,
+    pub response: PasskeyAttestationDto
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PasskeyAssertionDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "authenticatorData")]
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(rename = "userHandle")]
+    pub user_handle: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticationDtoRequest {
+    pub id: String,
+    pub response: PasskeyAssertionDto
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyDtoResponse {
+    pub id: Uuid,
+    pub credential_id: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollDtoResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollDtoRequest {
+    pub current_password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaConfirmDtoRequest {
+    pub code: String,
+    pub current_password: String
+}
+
+/// Changes to an enabled MFA take the password and a TOTP or recovery code.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChangeDtoRequest {
+    pub code: String,
+    pub current_password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaRecoveryCodesDtoResponse {
+    pub recovery_codes: Vec<String>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaVerifyDtoRequest {
+    pub challenge_token: String,
+    pub code: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeDtoResponse {
+    pub mfa_required: bool,
+    pub challenge_token: String
+}
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "customer_status", rename_all = "snake_case")]
+pub enum CustomerStatus {
+    PendingVerification,
+    Active,
+    Deleted,
+}
+
 #[derive(Clone, Debug, PartialEq, FromRow)]
 pub struct Customer {
     pub id: Uuid,
     pub email: String,
     pub password: String,
-    pub created_at: DateTime<Utc>
+    pub status: CustomerStatus,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub phone_number: Option<String>,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>
 }
 impl Customer {
+    /// Expects the password already hashed, see `HashingPool::encode`.
     pub fn new(
         email: String,
-        password: String
-    ) -> Result<Self, AppError> {
-        Ok(Self {
+        password_hashed: String
+    ) -> Self {
+        Self {
             id: Uuid::now_v7(),
             email,
-            password: HashBCrypt::encode(password)?,
-            created_at: Utc::now()
-        })
+            password: password_hashed,
+            status: CustomerStatus::PendingVerification,
+            email_verified_at: None,
+            phone_number: None,
+            name: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None
+        }
+    }
+
+    /// Unverified customers may still sign in for `EMAIL_VERIFICATION_GRACE_PERIOD_SECONDS` after sign-up.
+    pub fn ensure_verified(&self) -> Result<(), AppError> {
+        let grace_period = Duration::seconds(Environment::email_verification_grace_period_seconds());
+        if self.status == CustomerStatus::PendingVerification && self.created_at + grace_period <= Utc::now() {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                String::from("Email not verified"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
     }
 
     pub async fn insert(transaction: &mut Transaction<'_, Postgres>, customer: Self) -> Result<Self, AppError> {
         let query = r#"
         INSERT INTO customer
-            (id, email, password)
+            (id, email, password, status, email_verified_at)
         VALUES
-            ($1, $2, $3)
+            ($1, $2, $3, $4, $5)
         RETURNING *
         "#;
         let stored_customer: Self = sqlx::query_as(query)
             .bind(customer.id)
             .bind(customer.email)
             .bind(customer.password)
+            .bind(customer.status)
+            .bind(customer.email_verified_at)
             .fetch_one(&mut **transaction)
             .await
             .map_err(|err| err.to_business_error("insert", None))?;
+        CustomerRole::grant(transaction, stored_customer.id, Role::Customer).await?;
         Ok(stored_customer)
     }
 
@@ -62,13 +345,44 @@ impl Customer {
         transaction: &mut Transaction<'_, Postgres>, 
         email: String
     ) -> Result<Self, AppError> {
+        match Self::find_by(transaction, email).await? {
+            Some(customer) => Ok(customer),
+            None => Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("Customer not found"),
+                None,
+            )
+            .to_business_error()),
+        }
+    }
+
+    pub async fn find_by(
+        transaction: &mut Transaction<'_, Postgres>,
+        email: String
+    ) -> Result<Option<Self>, AppError> {
         let query = r#"
             SELECT * FROM customer
             WHERE email = $1
         "#;
-        let result = sqlx::query_as(query)
+        let customer = sqlx::query_as(query)
             .bind(email)
             .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get by", None))?;
+        Ok(customer)
+    }
+
+    pub async fn get_by_id(
+        transaction: &mut Transaction<'_, Postgres>,
+        id: Uuid
+    ) -> Result<Self, AppError> {
+        let query = r#"
+            SELECT * FROM customer
+            WHERE id = $1
+        "#;
+        let result = sqlx::query_as(query)
+            .bind(id)
+            .fetch_optional(&mut **transaction)
             .await;
         match result {
             Ok(customer) => {
@@ -83,7 +397,1522 @@ impl Customer {
                     .to_business_error())
                 }
             }
-            Err(err) => Err(err.to_business_error("get by", None)),
+            Err(err) => Err(err.to_business_error("get by id", None)),
+        }
+    }
+
+    /// Invalidates every access token issued to the customer before this second. Since `iat` has second
+    /// precision, tokens issued within the same second are kept, so those handed out right after the
+    /// revocation work, while earlier ones of that second are refused through their revoked sessions.
+    pub async fn revoke_tokens(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer
+            SET tokens_revoked_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke customer tokens", None))?;
+        Ok(())
+    }
+
+    pub async fn update_password(transaction: &mut Transaction<'_, Postgres>, id: Uuid, password_hashed: String) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer
+            SET password = $2, updated_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(password_hashed)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("update customer password", None))?;
+        Ok(())
+    }
+
+    pub async fn find_by_phone_number(
+        transaction: &mut Transaction<'_, Postgres>,
+        phone_number: &str
+    ) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer
+            WHERE phone_number = $1
+        "#;
+        let customer = sqlx::query_as(query)
+            .bind(phone_number)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get by phone number", None))?;
+        Ok(customer)
+    }
+
+    /// Expects a normalized E.164 phone number, see `PhoneNumberPolicy`. Returns 409 when another customer has it.
+    pub async fn ensure_phone_number_available(transaction: &mut Transaction<'_, Postgres>, id: Uuid, phone_number: &str) -> Result<(), AppError> {
+        let owner = Self::find_by_phone_number(transaction, phone_number).await?;
+        if owner.is_some_and(|owner| owner.id != id) {
+            return Err(AppErrorData::new(
+                StatusCode::CONFLICT,
+                String::from("Phone number already in use"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    pub async fn update_phone_number(transaction: &mut Transaction<'_, Postgres>, id: Uuid, phone_number: String) -> Result<(), AppError> {
+        Self::ensure_phone_number_available(transaction, id, &phone_number).await?;
+        let query = r#"
+            UPDATE customer
+            SET phone_number = $2, updated_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(phone_number)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("update customer phone number", None))?;
+        Ok(())
+    }
+
+    /// Records that a magic link is being sent, unless one was sent in the last
+    /// `MAGIC_LINK_RESEND_SECONDS`. Returns whether the link may be sent.
+    pub async fn mark_magic_link_sent(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE customer
+            SET magic_link_sent_at = now()
+            WHERE id = $1
+            AND (magic_link_sent_at IS NULL OR magic_link_sent_at <= $2)
+        "#;
+        let result = sqlx::query(query)
+            .bind(id)
+            .bind(Utc::now() - Duration::seconds(Environment::magic_link_resend_seconds()))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("mark magic link sent", None))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn verify_email(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer
+            SET status = 'active', email_verified_at = now(), updated_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("verify customer email", None))?;
+        Ok(())
+    }
+
+    /// Moves the customer to an address they just proved to own. Returns 409 when another
+    /// customer took it in the meantime.
+    pub async fn change_email(transaction: &mut Transaction<'_, Postgres>, id: Uuid, email: String) -> Result<(), AppError> {
+        Self::ensure_email_available(transaction, id, &email).await?;
+        let query = r#"
+            UPDATE customer
+            SET email = $2, status = 'active', email_verified_at = now(), updated_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(email)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("change customer email", None))?;
+        Ok(())
+    }
+
+    pub async fn ensure_email_available(transaction: &mut Transaction<'_, Postgres>, id: Uuid, email: &str) -> Result<(), AppError> {
+        let owner = Self::find_by(transaction, email.to_string()).await?;
+        if owner.is_some_and(|owner| owner.id != id) {
+            return Err(AppErrorData::new(
+                StatusCode::CONFLICT,
+                String::from("Email already in use"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    /// Keeps only the id and dates, the email is replaced by a tombstone no one can sign in with
+    /// and every token issued so far is revoked.
+    pub async fn anonymize(transaction: &mut Transaction<'_, Postgres>, id: Uuid, password_hashed: String) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer
+            SET email = $2,
+                password = $3,
+                phone_number = NULL,
+                name = NULL,
+                status = 'deleted',
+                deleted_at = now(),
+                updated_at = now(),
+                tokens_revoked_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(format!("deleted-{}@deleted.invalid", id))
+            .bind(password_hashed)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("anonymize customer", None))?;
+        Ok(())
+    }
+
+    pub async fn update_profile(transaction: &mut Transaction<'_, Postgres>, id: Uuid, name: Option<String>) -> Result<Self, AppError> {
+        let query = r#"
+            UPDATE customer
+            SET name = $2, updated_at = now()
+            WHERE id = $1
+            RETURNING *
+        "#;
+        let customer: Self = sqlx::query_as(query)
+            .bind(id)
+            .bind(name)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("update customer profile", None))?;
+        Ok(customer)
+    }
+}
+
+/// What an account may do, emitted as the `roles` claim of access tokens.
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "account_role", rename_all = "snake_case")]
+pub enum Role {
+    Customer,
+    /// Reviews the biometrics of any customer and changes their status.
+    Reviewer,
+    /// Holds every other role as well.
+    Admin,
+}
+
+pub struct CustomerRole;
+impl CustomerRole {
+    pub async fn find_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<Role>, AppError> {
+        let query = r#"
+            SELECT role FROM customer_role
+            WHERE customer_id = $1
+            ORDER BY role
+        "#;
+        sqlx::query_scalar(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find customer roles", None))
+    }
+
+    pub async fn grant(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, role: Role) -> Result<(), AppError> {
+        let query = r#"
+            INSERT INTO customer_role
+                (customer_id, role)
+            VALUES
+                ($1, $2)
+            ON CONFLICT DO NOTHING
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .bind(role)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("grant customer role", None))?;
+        Ok(())
+    }
+
+    /// Takes effect on tokens issued from now on, the ones already issued keep their roles until they expire.
+    pub async fn replace_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, roles: &[Role]) -> Result<Vec<Role>, AppError> {
+        let query = r#"
+            DELETE FROM customer_role
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke customer roles", None))?;
+        for role in roles {
+            Self::grant(transaction, customer_id, *role).await?;
         }
+        Self::find_all(transaction, customer_id).await
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct EmailVerificationToken {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    /// The new address of an email change, `None` when it verifies the current one.
+    pub email: Option<String>,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl EmailVerificationToken {
+    /// Returns the token to store together with the plain value mailed to the customer,
+    /// which is never persisted.
+    pub fn new(customer_id: Uuid, email: Option<String>) -> (Self, String) {
+        let plain_token = RandomToken::generate();
+        let verification_token = Self {
+            id: Uuid::now_v7(),
+            customer_id,
+            email,
+            token_hash: HashSha256::encode(&plain_token),
+            expires_at: Utc::now() + Duration::seconds(Environment::email_verification_ttl_seconds()),
+            used_at: None,
+            created_at: Utc::now()
+        };
+        (verification_token, plain_token)
+    }
+
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, verification_token: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO email_verification_token
+                (id, customer_id, email, token_hash, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            RETURNING *
+        "#;
+        let stored_verification_token: Self = sqlx::query_as(query)
+            .bind(verification_token.id)
+            .bind(verification_token.customer_id)
+            .bind(verification_token.email)
+            .bind(verification_token.token_hash)
+            .bind(verification_token.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert email verification token", None))?;
+        Ok(stored_verification_token)
+    }
+
+    pub async fn get_by(
+        transaction: &mut Transaction<'_, Postgres>,
+        plain_token: &str
+    ) -> Result<Self, AppError> {
+        let query = r#"
+            SELECT * FROM email_verification_token
+            WHERE token_hash = $1
+            FOR UPDATE
+        "#;
+        let result: Option<Self> = sqlx::query_as(query)
+            .bind(HashSha256::encode(plain_token))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get email verification token", None))?;
+        match result {
+            Some(verification_token) if verification_token.is_usable() => Ok(verification_token),
+            _ => Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("Invalid or expired verification token"),
+                None,
+            )
+            .to_business_error()),
+        }
+    }
+
+    /// Uses the given token and discards every other pending one of the customer.
+    pub async fn consume(transaction: &mut Transaction<'_, Postgres>, verification_token: &Self) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE email_verification_token
+            SET used_at = now()
+            WHERE customer_id = $1
+            AND used_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(verification_token.customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("consume email verification token", None))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl PasswordResetToken {
+    /// Returns the token to store together with the plain value mailed to the customer,
+    /// which is never persisted.
+    pub fn new(customer_id: Uuid) -> (Self, String) {
+        let plain_token = RandomToken::generate();
+        let reset_token = Self {
+            id: Uuid::now_v7(),
+            customer_id,
+            token_hash: HashSha256::encode(&plain_token),
+            expires_at: Utc::now() + Duration::seconds(Environment::password_reset_ttl_seconds()),
+            used_at: None,
+            created_at: Utc::now()
+        };
+        (reset_token, plain_token)
+    }
+
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    pub fn was_sent_recently(&self) -> bool {
+        self.created_at + Duration::seconds(Environment::password_reset_resend_seconds()) > Utc::now()
+    }
+
+    pub async fn find_latest(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM password_reset_token
+            WHERE customer_id = $1
+            ORDER BY created_at DESC
+            LIMIT 1
+        "#;
+        let reset_token: Option<Self> = sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get latest password reset token", None))?;
+        Ok(reset_token)
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, reset_token: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO password_reset_token
+                (id, customer_id, token_hash, expires_at)
+            VALUES
+                ($1, $2, $3, $4)
+            RETURNING *
+        "#;
+        let stored_reset_token: Self = sqlx::query_as(query)
+            .bind(reset_token.id)
+            .bind(reset_token.customer_id)
+            .bind(reset_token.token_hash)
+            .bind(reset_token.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert password reset token", None))?;
+        Ok(stored_reset_token)
+    }
+
+    pub async fn get_by(
+        transaction: &mut Transaction<'_, Postgres>,
+        plain_token: &str
+    ) -> Result<Self, AppError> {
+        let query = r#"
+            SELECT * FROM password_reset_token
+            WHERE token_hash = $1
+            FOR UPDATE
+        "#;
+        let result: Option<Self> = sqlx::query_as(query)
+            .bind(HashSha256::encode(plain_token))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get password reset token", None))?;
+        match result {
+            Some(reset_token) if reset_token.is_usable() => Ok(reset_token),
+            _ => Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("Invalid or expired reset token"),
+                None,
+            )
+            .to_business_error()),
+        }
+    }
+
+    /// Uses the given token and discards every other pending one of the customer.
+    pub async fn consume(transaction: &mut Transaction<'_, Postgres>, reset_token: &Self) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE password_reset_token
+            SET used_at = now()
+            WHERE customer_id = $1
+            AND used_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(reset_token.customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("consume password reset token", None))?;
+        Ok(())
+    }
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "sign_in_attempt_scope", rename_all = "snake_case")]
+pub enum SignInAttemptScope {
+    Email,
+    Ip,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct SignInAttempt {
+    pub scope: SignInAttemptScope,
+    pub identifier: String,
+    pub failures: i32,
+    pub last_failure_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>
+}
+impl SignInAttempt {
+    /// Returns 429 with `Retry-After` while the email or the client IP is locked out.
+    pub async fn ensure_not_locked(transaction: &mut Transaction<'_, Postgres>, email: &str, ip: &str) -> Result<(), AppError> {
+        let query = r#"
+            SELECT max(locked_until) FROM sign_in_attempt
+            WHERE ((scope = 'email' AND identifier = $1) OR (scope = 'ip' AND identifier = $2))
+            AND locked_until > now()
+        "#;
+        let locked_until: Option<DateTime<Utc>> = sqlx::query_scalar(query)
+            .bind(email)
+            .bind(ip)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get sign in lockout", None))?;
+        let Some(locked_until) = locked_until else {
+            return Ok(());
+        };
+        let retry_after = (locked_until - Utc::now()).num_seconds().max(0) + 1;
+        Err(AppErrorData::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            String::from("Too many failed sign in attempts"),
+            None,
+        )
+        .with_header(RETRY_AFTER, HeaderValue::from(retry_after))
+        .to_business_error())
+    }
+
+    pub async fn register_failure(transaction: &mut Transaction<'_, Postgres>, email: &str, ip: &str) -> Result<(), AppError> {
+        let policy = LockoutPolicy::from_env();
+        Self::increment(transaction, &policy, SignInAttemptScope::Email, email, policy.max_failures_per_email).await?;
+        Self::increment(transaction, &policy, SignInAttemptScope::Ip, ip, policy.max_failures_per_ip).await?;
+        Ok(())
+    }
+
+    /// Only the email counter is cleared on success, otherwise one valid account would let an IP keep guessing others.
+    pub async fn reset(transaction: &mut Transaction<'_, Postgres>, email: &str) -> Result<(), AppError> {
+        let query = r#"
+            DELETE FROM sign_in_attempt
+            WHERE scope = 'email'
+            AND identifier = $1
+        "#;
+        sqlx::query(query)
+            .bind(email)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("reset sign in attempts", None))?;
+        Ok(())
+    }
+
+    /// Failures older than the window start the count over.
+    async fn increment(
+        transaction: &mut Transaction<'_, Postgres>,
+        policy: &LockoutPolicy,
+        scope: SignInAttemptScope,
+        identifier: &str,
+        max_failures: i32
+    ) -> Result<(), AppError> {
+        let query = r#"
+            INSERT INTO sign_in_attempt
+                (scope, identifier, failures, last_failure_at)
+            VALUES
+                ($1, $2, 1, now())
+            ON CONFLICT (scope, identifier) DO UPDATE
+            SET failures = CASE
+                    WHEN sign_in_attempt.last_failure_at < now() - make_interval(secs => $3) THEN 1
+                    ELSE sign_in_attempt.failures + 1
+                END,
+                last_failure_at = now()
+            RETURNING *
+        "#;
+        let attempt: Self = sqlx::query_as(query)
+            .bind(scope)
+            .bind(identifier)
+            .bind(policy.failure_window_seconds as f64)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("register sign in failure", None))?;
+        let Some(lockout_seconds) = policy.lockout_seconds(attempt.failures, max_failures) else {
+            return Ok(());
+        };
+        let query = r#"
+            UPDATE sign_in_attempt
+            SET locked_until = now() + make_interval(secs => $3)
+            WHERE scope = $1
+            AND identifier = $2
+        "#;
+        sqlx::query(query)
+            .bind(scope)
+            .bind(identifier)
+            .bind(lockout_seconds as f64)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("lock sign in", None))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub family_id: Uuid,
+    pub customer_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl RefreshToken {
+    /// Returns the token to store together with the plain value handed to the customer,
+    /// which is never persisted.
+    pub fn new(customer_id: Uuid, family_id: Uuid) -> (Self, String) {
+        let plain_token = RandomToken::generate();
+        let refresh_token = Self {
+            id: Uuid::now_v7(),
+            family_id,
+            customer_id,
+            token_hash: HashSha256::encode(&plain_token),
+            expires_at: Utc::now() + Duration::seconds(Environment::refresh_token_ttl_seconds()),
+            rotated_at: None,
+            revoked_at: None,
+            created_at: Utc::now()
+        };
+        (refresh_token, plain_token)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn was_used(&self) -> bool {
+        self.rotated_at.is_some() || self.revoked_at.is_some()
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, refresh_token: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO refresh_token
+                (id, family_id, customer_id, token_hash, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            RETURNING *
+        "#;
+        let stored_refresh_token: Self = sqlx::query_as(query)
+            .bind(refresh_token.id)
+            .bind(refresh_token.family_id)
+            .bind(refresh_token.customer_id)
+            .bind(refresh_token.token_hash)
+            .bind(refresh_token.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert refresh token", None))?;
+        Ok(stored_refresh_token)
+    }
+
+    pub async fn get_by(
+        transaction: &mut Transaction<'_, Postgres>,
+        plain_token: &str
+    ) -> Result<Self, AppError> {
+        Self::find_by(transaction, plain_token).await?.ok_or_else(|| {
+            AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Invalid refresh token"),
+                None,
+            )
+            .to_business_error()
+        })
+    }
+
+    pub async fn find_by(
+        transaction: &mut Transaction<'_, Postgres>,
+        plain_token: &str
+    ) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM refresh_token
+            WHERE token_hash = $1
+            FOR UPDATE
+        "#;
+        sqlx::query_as(query)
+            .bind(HashSha256::encode(plain_token))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get refresh token", None))
+    }
+
+    pub async fn rotate(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE refresh_token
+            SET rotated_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("rotate refresh token", None))?;
+        Ok(())
+    }
+
+    pub async fn revoke_family(transaction: &mut Transaction<'_, Postgres>, family_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE refresh_token
+            SET revoked_at = now()
+            WHERE family_id = $1
+            AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(family_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke refresh token family", None))?;
+        Ok(())
+    }
+
+    pub async fn revoke_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE refresh_token
+            SET revoked_at = now()
+            WHERE customer_id = $1
+            AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke customer refresh tokens", None))?;
+        Ok(())
+    }
+
+    pub async fn revoke_others(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, family_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE refresh_token
+            SET revoked_at = now()
+            WHERE customer_id = $1
+            AND family_id <> $2
+            AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .bind(family_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke other refresh tokens", None))?;
+        Ok(())
+    }
+}
+
+/// A sign in on one device. Its id is the `family_id` of every refresh token rotated from
+/// that sign in and the `sid` claim of every access token issued along them.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct CustomerSession {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl CustomerSession {
+    pub fn new(customer_id: Uuid, device: ClientDevice) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            customer_id,
+            device_name: device.device_name,
+            user_agent: device.user_agent,
+            ip_address: device.ip,
+            last_seen_at: Utc::now(),
+            revoked_at: None,
+            created_at: Utc::now()
+        }
+    }
+
+    pub fn to_dto(self, current_session_id: Uuid) -> SessionDtoResponse {
+        SessionDtoResponse {
+            id: self.id,
+            device_name: self.device_name,
+            user_agent: self.user_agent,
+            ip_address: self.ip_address,
+            created_at: self.created_at.to_rfc3339(),
+            last_seen_at: self.last_seen_at.to_rfc3339(),
+            current: self.id == current_session_id
+        }
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, session: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO customer_session
+                (id, customer_id, device_name, user_agent, ip_address)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            RETURNING *
+        "#;
+        let stored_session: Self = sqlx::query_as(query)
+            .bind(session.id)
+            .bind(session.customer_id)
+            .bind(session.device_name)
+            .bind(session.user_agent)
+            .bind(session.ip_address)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert customer session", None))?;
+        Ok(stored_session)
+    }
+
+    /// Sessions whose last refresh token can no longer be used are left out, they cannot come back.
+    pub async fn find_active(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer_session
+            WHERE customer_id = $1
+            AND revoked_at IS NULL
+            AND last_seen_at > $2
+            ORDER BY last_seen_at DESC
+        "#;
+        let sessions: Vec<Self> = sqlx::query_as(query)
+            .bind(customer_id)
+            .bind(Utc::now() - Duration::seconds(Environment::refresh_token_ttl_seconds()))
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get customer sessions", None))?;
+        Ok(sessions)
+    }
+
+    /// Revoked and expired sessions included, newest first.
+    pub async fn find_all_by_customer(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer_session
+            WHERE customer_id = $1
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get all customer sessions", None))
+    }
+
+    /// Tokens issued before sessions existed have no row, only an explicit revocation counts.
+    pub async fn is_revoked(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            SELECT EXISTS(
+                SELECT 1 FROM customer_session
+                WHERE id = $1
+                AND revoked_at IS NOT NULL
+            )
+        "#;
+        let revoked: bool = sqlx::query_scalar(query)
+            .bind(id)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("check customer session", None))?;
+        Ok(revoked)
+    }
+
+    pub async fn touch(transaction: &mut Transaction<'_, Postgres>, id: Uuid, ip_address: &str) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_session
+            SET last_seen_at = now(), ip_address = $2
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(ip_address)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("update customer session", None))?;
+        Ok(())
+    }
+
+    /// Also revokes the session's refresh tokens. Returns false when the customer has no such active session.
+    pub async fn revoke(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE customer_session
+            SET revoked_at = now()
+            WHERE id = $1
+            AND customer_id = $2
+            AND revoked_at IS NULL
+        "#;
+        let result = sqlx::query(query)
+            .bind(id)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke customer session", None))?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        RefreshToken::revoke_family(transaction, id).await?;
+        Ok(true)
+    }
+
+    /// Also revokes every refresh token of the customer.
+    pub async fn revoke_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_session
+            SET revoked_at = now()
+            WHERE customer_id = $1
+            AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke customer sessions", None))?;
+        RefreshToken::revoke_all(transaction, customer_id).await
+    }
+
+    /// Like `revoke_all`, but the session making the request stays signed in.
+    pub async fn revoke_others(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_session
+            SET revoked_at = now()
+            WHERE customer_id = $1
+            AND id <> $2
+            AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke other customer sessions", None))?;
+        RefreshToken::revoke_others(transaction, customer_id, id).await
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct RevokedToken {
+    pub jti: Uuid,
+    pub customer_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+impl RevokedToken {
+    pub fn new<C: RevocableClaims>(claims: &C) -> Self {
+        Self {
+            jti: claims.jti(),
+            customer_id: claims.sub(),
+            expires_at: claims.expires_at(),
+            created_at: Utc::now()
+        }
+    }
+
+    /// Entries are only useful until the token they deny expires, so expired ones are purged on every insert.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, revoked_token: Self) -> Result<(), AppError> {
+        let purge_query = r#"
+            DELETE FROM revoked_token
+            WHERE expires_at < now()
+        "#;
+        sqlx::query(purge_query)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("purge revoked tokens", None))?;
+        let query = r#"
+            INSERT INTO revoked_token
+                (jti, customer_id, expires_at)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+        "#;
+        sqlx::query(query)
+            .bind(revoked_token.jti)
+            .bind(revoked_token.customer_id)
+            .bind(revoked_token.expires_at)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert revoked token", None))?;
+        Ok(())
+    }
+
+    pub async fn is_revoked<C: RevocableClaims>(transaction: &mut Transaction<'_, Postgres>, claims: &C) -> Result<bool, AppError> {
+        let query = r#"
+            SELECT EXISTS (
+                SELECT 1 FROM revoked_token
+                WHERE jti = $1
+                AND expires_at >= now()
+            ) OR EXISTS (
+                SELECT 1 FROM customer
+                WHERE id = $2
+                AND (date_trunc('second', tokens_revoked_at) > $3 OR status = 'deleted')
+            )
+        "#;
+        let revoked: bool = sqlx::query_scalar(query)
+            .bind(claims.jti())
+            .bind(claims.sub())
+            .bind(claims.issued_at())
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("check revoked token", None))?;
+        Ok(revoked)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct CustomerMfa {
+    pub customer_id: Uuid,
+    pub secret_encrypted: String,
+    pub last_used_step: Option<i64>,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl CustomerMfa {
+    /// Expects the TOTP secret already encrypted, see `HashAesGcm::encrypt`.
+    pub fn new(customer_id: Uuid, secret_encrypted: String) -> Self {
+        Self {
+            customer_id,
+            secret_encrypted,
+            last_used_step: None,
+            enabled_at: None,
+            created_at: Utc::now()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Returns the TOTP step the code belongs to, or `None` when it is wrong or was already used.
+    pub fn verify_code(&self, account_name: String, code: &str) -> Result<Option<i64>, AppError> {
+        let secret = HashAesGcm::from_env()?.decrypt(&self.secret_encrypted)?;
+        Ok(Totp::new(secret, account_name).verify(code, Utc::now(), self.last_used_step))
+    }
+
+    /// A pending enrollment is replaced, so customers who lost the first secret can start over.
+    pub async fn upsert(transaction: &mut Transaction<'_, Postgres>, mfa: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO customer_mfa
+                (customer_id, secret_encrypted)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (customer_id) DO UPDATE
+            SET secret_encrypted = excluded.secret_encrypted,
+                last_used_step = NULL,
+                enabled_at = NULL,
+                created_at = now()
+            RETURNING *
+        "#;
+        let stored_mfa: Self = sqlx::query_as(query)
+            .bind(mfa.customer_id)
+            .bind(mfa.secret_encrypted)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("upsert customer mfa", None))?;
+        Ok(stored_mfa)
+    }
+
+    pub async fn find_by(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer_mfa
+            WHERE customer_id = $1
+        "#;
+        let mfa: Option<Self> = sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get customer mfa", None))?;
+        Ok(mfa)
+    }
+
+    pub async fn enable(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, step: i64) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_mfa
+            SET enabled_at = now(), last_used_step = $2
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .bind(step)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("enable customer mfa", None))?;
+        Ok(())
+    }
+
+    /// Removes the MFA together with its recovery codes, sign ins go back to the password alone.
+    pub async fn delete(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<(), AppError> {
+        MfaRecoveryCode::replace_all(transaction, customer_id, Vec::new()).await?;
+        let query = r#"
+            DELETE FROM customer_mfa
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("delete customer mfa", None))?;
+        Ok(())
+    }
+
+    /// Returns false when a concurrent request already used this or a later step.
+    pub async fn use_step(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, step: i64) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE customer_mfa
+            SET last_used_step = $2
+            WHERE customer_id = $1
+            AND (last_used_step IS NULL OR last_used_step < $2)
+        "#;
+        let result = sqlx::query(query)
+            .bind(customer_id)
+            .bind(step)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("use customer mfa step", None))?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct MfaRecoveryCode {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl MfaRecoveryCode {
+    /// Returns the code to store together with the plain value shown once to the customer,
+    /// which is never persisted.
+    pub fn new(customer_id: Uuid) -> (Self, String) {
+        let plain_code = RandomCode::generate();
+        let recovery_code = Self {
+            id: Uuid::now_v7(),
+            customer_id,
+            code_hash: HashSha256::encode(&Self::normalize(&plain_code)),
+            used_at: None,
+            created_at: Utc::now()
+        };
+        (recovery_code, plain_code)
+    }
+
+    /// Codes are accepted regardless of case, spaces and dashes.
+    pub fn normalize(code: &str) -> String {
+        code.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+
+    /// The previous codes of the customer stop working once new ones are issued.
+    pub async fn replace_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, recovery_codes: Vec<Self>) -> Result<(), AppError> {
+        let delete_query = r#"
+            DELETE FROM mfa_recovery_code
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(delete_query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("delete mfa recovery codes", None))?;
+        let query = r#"
+            INSERT INTO mfa_recovery_code
+                (id, customer_id, code_hash)
+            VALUES
+                ($1, $2, $3)
+        "#;
+        for recovery_code in recovery_codes {
+            sqlx::query(query)
+                .bind(recovery_code.id)
+                .bind(recovery_code.customer_id)
+                .bind(recovery_code.code_hash)
+                .execute(&mut **transaction)
+                .await
+                .map_err(|err| err.to_business_error("insert mfa recovery code", None))?;
+        }
+        Ok(())
+    }
+
+    /// Marks the code used and returns false when it does not exist or was used before.
+    pub async fn consume(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid, plain_code: &str) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE mfa_recovery_code
+            SET used_at = now()
+            WHERE customer_id = $1
+            AND code_hash = $2
+            AND used_at IS NULL
+        "#;
+        let result = sqlx::query(query)
+            .bind(customer_id)
+            .bind(HashSha256::encode(&Self::normalize(plain_code)))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("consume mfa recovery code", None))?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct ConsumedMagicLink {
+    pub jti: Uuid,
+    pub customer_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: DateTime<Utc>
+}
+impl ConsumedMagicLink {
+    pub fn new(claims: &MagicLinkClaims) -> Self {
+        Self {
+            jti: claims.jti,
+            customer_id: claims.sub,
+            expires_at: claims.expires_at(),
+            consumed_at: Utc::now()
+        }
+    }
+
+    /// Returns false when the link was consumed before. Entries are only useful until the link
+    /// expires, so expired ones are purged on every insert.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, consumed_link: Self) -> Result<bool, AppError> {
+        let purge_query = r#"
+            DELETE FROM consumed_magic_link
+            WHERE expires_at < now()
+        "#;
+        sqlx::query(purge_query)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("purge consumed magic links", None))?;
+        let query = r#"
+            INSERT INTO consumed_magic_link
+                (jti, customer_id, expires_at)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+        "#;
+        let result = sqlx::query(query)
+            .bind(consumed_link.jti)
+            .bind(consumed_link.customer_id)
+            .bind(consumed_link.expires_at)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert consumed magic link", None))?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct SmsOtp {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    /// The new number of a phone number change, `None` when the code signs in.
+    pub phone_number: Option<String>,
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl SmsOtp {
+    /// Returns the code to store together with the plain value texted to the customer,
+    /// which is never persisted.
+    pub fn new(customer_id: Uuid, phone_number: Option<String>) -> (Self, String) {
+        let plain_code = RandomCode::generate_digits();
+        let id = Uuid::now_v7();
+        let otp = Self {
+            id,
+            customer_id,
+            phone_number,
+            code_hash: Self::hash(id, &plain_code),
+            attempts: 0,
+            expires_at: Utc::now() + Duration::seconds(Environment::sms_otp_ttl_seconds()),
+            used_at: None,
+            created_at: Utc::now()
+        };
+        (otp, plain_code)
+    }
+
+    /// Six digits are few enough to guess, so a code also stops working after `SMS_OTP_MAX_ATTEMPTS` wrong tries.
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none()
+            && self.expires_at > Utc::now()
+            && self.attempts < Environment::sms_otp_max_attempts()
+    }
+
+    pub fn was_sent_recently(&self) -> bool {
+        self.created_at + Duration::seconds(Environment::sms_otp_resend_seconds()) > Utc::now()
+    }
+
+    pub fn matches(&self, plain_code: &str) -> bool {
+        Self::hash(self.id, plain_code.trim()) == self.code_hash
+    }
+
+    /// Salted with the id, so equal codes do not share a hash.
+    fn hash(id: Uuid, plain_code: &str) -> String {
+        HashSha256::encode(&format!("{}:{}", id, plain_code))
+    }
+
+    /// Only the newest code of a customer is valid, older ones of the same kind are discarded.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, otp: Self) -> Result<Self, AppError> {
+        let discard_query = r#"
+            UPDATE sms_otp
+            SET used_at = now()
+            WHERE customer_id = $1
+            AND (phone_number IS NOT NULL) = $2
+            AND used_at IS NULL
+        "#;
+        sqlx::query(discard_query)
+            .bind(otp.customer_id)
+            .bind(otp.phone_number.is_some())
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("discard sms otps", None))?;
+        let query = r#"
+            INSERT INTO sms_otp
+                (id, customer_id, phone_number, code_hash, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            RETURNING *
+        "#;
+        let stored_otp: Self = sqlx::query_as(query)
+            .bind(otp.id)
+            .bind(otp.customer_id)
+            .bind(otp.phone_number)
+            .bind(otp.code_hash)
+            .bind(otp.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert sms otp", None))?;
+        Ok(stored_otp)
+    }
+
+    /// The latest sign in code. Locks the row, so concurrent guesses are counted one after the other.
+    pub async fn find_latest(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Option<Self>, AppError> {
+        Self::find_latest_of_kind(transaction, customer_id, false).await
+    }
+
+    /// The latest code confirming a phone number change, locked like `find_latest` does.
+    pub async fn find_latest_phone_change(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Option<Self>, AppError> {
+        Self::find_latest_of_kind(transaction, customer_id, true).await
+    }
+
+    async fn find_latest_of_kind(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid,
+        phone_change: bool
+    ) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM sms_otp
+            WHERE customer_id = $1
+            AND (phone_number IS NOT NULL) = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            FOR UPDATE
+        "#;
+        let otp: Option<Self> = sqlx::query_as(query)
+            .bind(customer_id)
+            .bind(phone_change)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get sms otp", None))?;
+        Ok(otp)
+    }
+
+    pub async fn register_failed_attempt(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE sms_otp
+            SET attempts = attempts + 1
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("register sms otp attempt", None))?;
+        Ok(())
+    }
+
+    pub async fn consume(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE sms_otp
+            SET used_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("consume sms otp", None))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct PasskeyCredential {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    /// Base64url, the way browsers report it in `PublicKeyCredential.id`.
+    pub credential_id: String,
+    /// COSE encoded, see `CosePublicKey`.
+    pub public_key: Vec<u8>,
+    pub sign_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl PasskeyCredential {
+    pub fn new(customer_id: Uuid, credential_id: String, public_key: Vec<u8>, sign_count: u32) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            customer_id,
+            credential_id,
+            public_key,
+            sign_count: i64::from(sign_count),
+            last_used_at: None,
+            created_at: Utc::now()
+        }
+    }
+
+    /// Returns 409 when the credential is already registered, by this or another customer.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, credential: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO passkey_credential
+                (id, customer_id, credential_id, public_key, sign_count)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            ON CONFLICT (credential_id) DO NOTHING
+            RETURNING *
+        "#;
+        let stored_credential: Option<Self> = sqlx::query_as(query)
+            .bind(credential.id)
+            .bind(credential.customer_id)
+            .bind(credential.credential_id)
+            .bind(credential.public_key)
+            .bind(credential.sign_count)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert passkey credential", None))?;
+        stored_credential.ok_or_else(|| {
+            AppErrorData::new(
+                StatusCode::CONFLICT,
+                String::from("Passkey already registered"),
+                None,
+            )
+            .to_business_error()
+        })
+    }
+
+    pub async fn find_by_credential_id(transaction: &mut Transaction<'_, Postgres>, credential_id: &str) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM passkey_credential
+            WHERE credential_id = $1
+            FOR UPDATE
+        "#;
+        let credential: Option<Self> = sqlx::query_as(query)
+            .bind(credential_id)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get passkey credential", None))?;
+        Ok(credential)
+    }
+
+    pub async fn find_all_by_customer(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM passkey_credential
+            WHERE customer_id = $1
+            ORDER BY created_at
+        "#;
+        let credentials: Vec<Self> = sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("get passkey credentials", None))?;
+        Ok(credentials)
+    }
+
+    pub fn stored_sign_count(&self) -> u32 {
+        u32::try_from(self.sign_count).unwrap_or(u32::MAX)
+    }
+
+    pub async fn register_use(transaction: &mut Transaction<'_, Postgres>, id: Uuid, sign_count: u32) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE passkey_credential
+            SET sign_count = $2, last_used_at = now()
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(i64::from(sign_count))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("update passkey credential", None))?;
+        Ok(())
+    }
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "passkey_ceremony", rename_all = "snake_case")]
+pub enum PasskeyCeremony {
+    Registration,
+    Authentication,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct PasskeyChallenge {
+    pub challenge: String,
+    pub ceremony: PasskeyCeremony,
+    pub customer_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+impl PasskeyChallenge {
+    pub fn new(ceremony: PasskeyCeremony, customer_id: Option<Uuid>) -> Self {
+        Self {
+            challenge: RandomToken::generate(),
+            ceremony,
+            customer_id,
+            expires_at: Utc::now() + Duration::seconds(Environment::webauthn_challenge_ttl_seconds()),
+            created_at: Utc::now()
+        }
+    }
+
+    /// Challenges are only useful until they expire, so expired ones are purged on every insert.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, challenge: Self) -> Result<Self, AppError> {
+        let purge_query = r#"
+            DELETE FROM passkey_challenge
+            WHERE expires_at < now()
+        "#;
+        sqlx::query(purge_query)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("purge passkey challenges", None))?;
+        let query = r#"
+            INSERT INTO passkey_challenge
+                (challenge, ceremony, customer_id, expires_at)
+            VALUES
+                ($1, $2, $3, $4)
+            RETURNING *
+        "#;
+        let stored_challenge: Self = sqlx::query_as(query)
+            .bind(challenge.challenge)
+            .bind(challenge.ceremony)
+            .bind(challenge.customer_id)
+            .bind(challenge.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert passkey challenge", None))?;
+        Ok(stored_challenge)
+    }
+
+    /// Deletes the challenge while reading it, so each one is answered only once.
+    pub async fn take(transaction: &mut Transaction<'_, Postgres>, challenge: &str, ceremony: PasskeyCeremony) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            DELETE FROM passkey_challenge
+            WHERE challenge = $1
+            AND ceremony = $2
+            AND expires_at > now()
+            RETURNING *
+        "#;
+        let taken_challenge: Option<Self> = sqlx::query_as(query)
+            .bind(challenge)
+            .bind(ceremony)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("take passkey challenge", None))?;
+        Ok(taken_challenge)
     }
 }
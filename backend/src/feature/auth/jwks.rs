@@ -0,0 +1,13 @@
+use axum::extract::State;
+use jsonwebtoken::jwk::JwkSet;
+
+use crate::{infra::{axum::AppJsonResponse, errors::AppError}, state::AppState};
+
+pub struct JwksUseCase;
+impl JwksUseCase {
+    pub async fn jwks(
+        State(app_state): State<AppState>,
+    ) -> Result<AppJsonResponse<JwkSet>, AppError> {
+        Ok(AppJsonResponse::new(app_state.jwt_key_ring.jwks()))
+    }
+}
@@ -0,0 +1,32 @@
+use axum::extract::State;
+use hyper::StatusCode;
+
+use crate::{infra::{axum::AppJsonRequest, errors::AppError}, state::AppState};
+
+use super::{authorization::AuthorizedCustomer, domain::{Customer, CustomerSession, LogoutDtoRequest, RefreshToken, RevokedToken}};
+
+pub struct LogoutUseCase;
+impl LogoutUseCase {
+    pub async fn logout(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<LogoutDtoRequest>
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        RevokedToken::insert(&mut transaction, RevokedToken::new(&claims)).await?;
+        CustomerSession::revoke(&mut transaction, claims.sub, claims.sid).await?;
+        // A stale or foreign refresh token is ignored, it must not keep the access token and session alive.
+        if let Some(refresh_token) = request.refresh_token.filter(|token| !token.trim().is_empty()) {
+            let refresh_token = RefreshToken::find_by(&mut transaction, &refresh_token).await?;
+            if let Some(refresh_token) = refresh_token.filter(|token| token.customer_id == claims.sub) {
+                RefreshToken::revoke_family(&mut transaction, refresh_token.family_id).await?;
+            }
+        }
+        if request.everywhere {
+            CustomerSession::revoke_all(&mut transaction, claims.sub).await?;
+            Customer::revoke_tokens(&mut transaction, claims.sub).await?;
+        }
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -0,0 +1,74 @@
+use axum::{extract::State, response::Response};
+use hyper::StatusCode;
+use tracing::error;
+
+use crate::{infra::{axum::{AppJsonRequest, ClientDevice}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState, support::{jwt::{Jwt, MagicLinkClaims}, mail::MailMessage}};
+
+use super::{domain::{ConsumeMagicLinkDtoRequest, ConsumedMagicLink, Customer, MagicLinkDtoRequest, RevokedToken}, policy::EmailPolicy, sing_in::SingInUseCase, validators::Validator};
+
+pub struct MagicLinkUseCase;
+impl MagicLinkUseCase {
+    /// Answers right away and does the lookup in the background, so neither the response
+    /// nor its timing reveals whether the email is registered.
+    pub async fn request(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<MagicLinkDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let email = EmailPolicy::normalize(&request.email);
+        tokio::spawn(async move {
+            if let Err(err) = Self::send_magic_link(&app_state, email).await {
+                error!("Failed to send magic link mail: {:?}", err);
+            }
+        });
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    /// Answers like `SingInUseCase::sing_in` does. Links issued before the customer's tokens
+    /// were revoked, e.g. by a password reset, are refused as well.
+    pub async fn consume(
+        State(app_state): State<AppState>,
+        device: ClientDevice,
+        AppJsonRequest(request): AppJsonRequest<ConsumeMagicLinkDtoRequest>,
+    ) -> Result<Response, AppError> {
+        Validator::token_not_empty(&request.token)?;
+        let claims = MagicLinkClaims::extract_jwt(request.token, &app_state.jwt_key_ring)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        if RevokedToken::is_revoked(&mut transaction, &claims).await?
+            || !ConsumedMagicLink::insert(&mut transaction, ConsumedMagicLink::new(&claims)).await?
+        {
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Magic link already used"),
+                None,
+            )
+            .to_business_error());
+        }
+        let customer = Customer::get_by_id(&mut transaction, claims.sub).await?;
+        customer.ensure_verified()?;
+        SingInUseCase::complete(&app_state, transaction, customer, device).await
+    }
+
+    /// A new link is only mailed once `MAGIC_LINK_RESEND_SECONDS` passed since the last one.
+    async fn send_magic_link(app_state: &AppState, email: String) -> Result<(), AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let Some(customer) = Customer::find_by(&mut transaction, email).await? else {
+            return Ok(());
+        };
+        if !Customer::mark_magic_link_sent(&mut transaction, customer.id).await? {
+            return Ok(());
+        }
+        app_state.commit_transaction(transaction).await?;
+        let claims = MagicLinkClaims::new(customer.id, Environment::magic_link_ttl_seconds());
+        let token = MagicLinkClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?;
+        app_state.mail_sender.send(MailMessage {
+            to: customer.email,
+            subject: String::from("Your sign in link"),
+            body: format!(
+                "Sign in by opening the link below, it works once and expires in {} minutes:\n\n{}?token={}\n\nIf you did not ask for it, ignore this mail.",
+                Environment::magic_link_ttl_seconds() / 60,
+                Environment::magic_link_url(),
+                token
+            ),
+        }).await
+    }
+}
@@ -0,0 +1,212 @@
+use axum::{extract::State, response::Response};
+use hyper::StatusCode;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse, ClientDevice, ClientIp}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState, support::{hash::hash_aes_gcm::HashAesGcm, jwt::{Jwt, MfaChallengeClaims}, totp::Totp}};
+
+use super::{authorization::AuthorizedCustomer, domain::{Customer, CustomerMfa, MfaChallengeDtoResponse, MfaChangeDtoRequest, MfaConfirmDtoRequest, MfaEnrollDtoRequest, MfaEnrollDtoResponse, MfaRecoveryCode, MfaRecoveryCodesDtoResponse, MfaVerifyDtoRequest, RevokedToken, SignInAttempt}, password::PasswordUseCase, tokens::AuthTokens, validators::Validator};
+
+pub struct MfaUseCase;
+impl MfaUseCase {
+    /// Starts or restarts an enrollment. MFA is only enforced once the first code is confirmed,
+    /// the recovery codes are shown here and never again.
+    pub async fn enroll(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<MfaEnrollDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<MfaEnrollDtoResponse>), AppError> {
+        Validator::current_password_not_empty(&request.current_password)?;
+        PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let current_mfa = CustomerMfa::find_by(&mut transaction, claims.sub).await?;
+        if current_mfa.is_some_and(|mfa| mfa.is_enabled()) {
+            return Err(AppErrorData::new(
+                StatusCode::CONFLICT,
+                String::from("MFA already enabled"),
+                None,
+            )
+            .to_business_error());
+        }
+        let secret = Totp::generate_secret();
+        let secret_encrypted = HashAesGcm::from_env()?.encrypt(&secret)?;
+        let totp = Totp::new(secret, claims.customer_email.clone());
+        CustomerMfa::upsert(&mut transaction, CustomerMfa::new(claims.sub, secret_encrypted)).await?;
+        let recovery_codes = Self::replace_recovery_codes(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok((
+            StatusCode::CREATED,
+            AppJsonResponse::new(MfaEnrollDtoResponse {
+                secret: totp.secret_base32(),
+                otpauth_uri: totp.otpauth_uri(),
+                recovery_codes,
+            })
+        ))
+    }
+
+    pub async fn confirm(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<MfaConfirmDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        Validator::code_not_empty(&request.code)?;
+        Validator::current_password_not_empty(&request.current_password)?;
+        PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let mfa = CustomerMfa::find_by(&mut transaction, claims.sub)
+            .await?
+            .filter(|mfa| !mfa.is_enabled())
+            .ok_or_else(|| {
+                AppErrorData::new(
+                    StatusCode::BAD_REQUEST,
+                    String::from("No pending MFA enrollment"),
+                    None,
+                )
+                .to_business_error()
+            })?;
+        let step = mfa.verify_code(claims.customer_email, &request.code)?.ok_or_else(invalid_code)?;
+        CustomerMfa::enable(&mut transaction, claims.sub, step).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn disable(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<MfaChangeDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = Self::prove_both_factors(&app_state, claims.sub, request, &client_ip).await?;
+        CustomerMfa::delete(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// The previous recovery codes stop working, the new ones are shown here and never again.
+    pub async fn regenerate_recovery_codes(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<MfaChangeDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<MfaRecoveryCodesDtoResponse>), AppError> {
+        let mut transaction = Self::prove_both_factors(&app_state, claims.sub, request, &client_ip).await?;
+        let recovery_codes = Self::replace_recovery_codes(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok((StatusCode::CREATED, AppJsonResponse::new(MfaRecoveryCodesDtoResponse { recovery_codes })))
+    }
+
+    /// Exchanges the challenge from `sing_in` and a TOTP or recovery code for the real tokens.
+    /// Wrong codes count as failed sign ins, so they are locked out the same way.
+    pub async fn verify(
+        State(app_state): State<AppState>,
+        device: ClientDevice,
+        AppJsonRequest(request): AppJsonRequest<MfaVerifyDtoRequest>,
+    ) -> Result<Response, AppError> {
+        Validator::token_not_empty(&request.challenge_token)?;
+        Validator::code_not_empty(&request.code)?;
+        let claims = MfaChallengeClaims::extract_jwt(request.challenge_token, &app_state.jwt_key_ring)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        if RevokedToken::is_revoked(&mut transaction, &claims).await? {
+            return Err(no_access());
+        }
+        let customer = Customer::get_by_id(&mut transaction, claims.sub).await?;
+        SignInAttempt::ensure_not_locked(&mut transaction, &customer.email, &device.ip).await?;
+        let mfa = CustomerMfa::find_by(&mut transaction, customer.id)
+            .await?
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or_else(no_access)?;
+        if !Self::accepts_code(&mut transaction, &customer, &mfa, &request.code).await? {
+            // The failure has to be stored even though the request fails.
+            SignInAttempt::register_failure(&mut transaction, &customer.email, &device.ip).await?;
+            app_state.commit_transaction(transaction).await?;
+            return Err(no_access());
+        }
+        RevokedToken::insert(&mut transaction, RevokedToken::new(&claims)).await?;
+        SignInAttempt::reset(&mut transaction, &customer.email).await?;
+        let tokens = AuthTokens::start_session(&app_state, &mut transaction, &customer, device).await?;
+        app_state.commit_transaction(transaction).await?;
+        tokens.into_response(customer)
+    }
+
+    /// A TOTP code is accepted once per step, a recovery code only once.
+    async fn accepts_code(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer: &Customer,
+        mfa: &CustomerMfa,
+        code: &str
+    ) -> Result<bool, AppError> {
+        match mfa.verify_code(customer.email.clone(), code)? {
+            Some(step) => CustomerMfa::use_step(transaction, customer.id, step).await,
+            None => MfaRecoveryCode::consume(transaction, customer.id, code).await,
+        }
+    }
+
+    /// Checks the current password and a code of the enabled MFA. Wrong codes count as failed
+    /// sign ins, like in `verify`. Returns the transaction to carry out the change in.
+    async fn prove_both_factors<'a>(
+        app_state: &'a AppState,
+        customer_id: Uuid,
+        request: MfaChangeDtoRequest,
+        client_ip: &str
+    ) -> Result<Transaction<'a, Postgres>, AppError> {
+        Validator::code_not_empty(&request.code)?;
+        Validator::current_password_not_empty(&request.current_password)?;
+        let customer = PasswordUseCase::verify_current(app_state, customer_id, request.current_password, client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let mfa = CustomerMfa::find_by(&mut transaction, customer.id)
+            .await?
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or_else(|| {
+                AppErrorData::new(
+                    StatusCode::BAD_REQUEST,
+                    String::from("MFA not enabled"),
+                    None,
+                )
+                .to_business_error()
+            })?;
+        if !Self::accepts_code(&mut transaction, &customer, &mfa, &request.code).await? {
+            // The failure has to be stored even though the request fails.
+            SignInAttempt::register_failure(&mut transaction, &customer.email, client_ip).await?;
+            app_state.commit_transaction(transaction).await?;
+            return Err(invalid_code());
+        }
+        Ok(transaction)
+    }
+
+    /// Returns the plain codes, which are never persisted.
+    async fn replace_recovery_codes(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<String>, AppError> {
+        let (recovery_codes, plain_recovery_codes): (Vec<_>, Vec<_>) = (0..Environment::mfa_recovery_codes())
+            .map(|_| MfaRecoveryCode::new(customer_id))
+            .unzip();
+        MfaRecoveryCode::replace_all(transaction, customer_id, recovery_codes).await?;
+        Ok(plain_recovery_codes)
+    }
+
+    pub fn challenge(app_state: &AppState, customer: &Customer) -> Result<AppJsonResponse<MfaChallengeDtoResponse>, AppError> {
+        let claims = MfaChallengeClaims::new(customer.id, Environment::mfa_challenge_ttl_seconds());
+        Ok(AppJsonResponse::new(MfaChallengeDtoResponse {
+            mfa_required: true,
+            challenge_token: MfaChallengeClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?,
+        }))
+    }
+}
+
+fn invalid_code() -> AppError {
+    AppErrorData::new(
+        StatusCode::BAD_REQUEST,
+        String::from("Invalid MFA code"),
+        None,
+    )
+    .to_business_error()
+}
+
+fn no_access() -> AppError {
+    AppErrorData::new(
+        StatusCode::UNAUTHORIZED,
+        String::from("No access"),
+        None,
+    )
+    .to_business_error()
+}
@@ -1,4 +1,17 @@
+pub mod authorization;
+pub mod jwks;
+pub mod logout;
+pub mod magic_link;
+pub mod mfa;
+pub mod passkey;
+pub mod password;
+pub mod policy;
+pub mod refresh;
+pub mod sessions;
 pub mod sing_in;
 pub mod sing_up;
+pub mod sms;
 pub mod domain;
+pub mod tokens;
 pub mod validators;
+pub mod verify_email;
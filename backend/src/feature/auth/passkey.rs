@@ -0,0 +1,177 @@
+use axum::{extract::State, response::Response};
+use data_encoding::BASE64URL_NOPAD;
+use hyper::StatusCode;
+
+use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse, ClientDevice}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState, support::webauthn::{decode_base64url, WebAuthn, COSE_ALGORITHM_ES256}};
+
+use super::{authorization::AuthorizedCustomer, domain::{Customer, PasskeyAuthenticationDtoRequest, PasskeyAuthenticationOptionsDtoResponse, PasskeyAuthenticatorSelectionDto, PasskeyCeremony, PasskeyChallenge, PasskeyCredential, PasskeyCredentialDescriptorDto, PasskeyCredentialParameterDto, PasskeyDtoResponse, PasskeyRegistrationDtoRequest, PasskeyRegistrationOptionsDtoResponse, PasskeyRelyingPartyDto, PasskeyUserDto}, tokens::AuthTokens};
+
+const PUBLIC_KEY: &str = "public-key";
+const REQUIRED: &str = "required";
+
+pub struct PasskeyUseCase;
+impl PasskeyUseCase {
+    pub async fn registration_options(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+    ) -> Result<AppJsonResponse<PasskeyRegistrationOptionsDtoResponse>, AppError> {
+        let web_authn = WebAuthn::from_env();
+        let mut transaction = app_state.begin_transaction().await?;
+        let challenge = PasskeyChallenge::insert(
+            &mut transaction,
+            PasskeyChallenge::new(PasskeyCeremony::Registration, Some(claims.sub)),
+        ).await?;
+        let credentials = PasskeyCredential::find_all_by_customer(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(PasskeyRegistrationOptionsDtoResponse {
+            challenge: challenge.challenge,
+            rp: PasskeyRelyingPartyDto {
+                id: web_authn.rp_id().to_string(),
+                name: Environment::webauthn_rp_name(),
+            },
+            user: PasskeyUserDto {
+                id: BASE64URL_NOPAD.encode(claims.sub.as_bytes()),
+                name: claims.customer_email.clone(),
+                display_name: claims.customer_email,
+            },
+            pub_key_cred_params: vec![PasskeyCredentialParameterDto {
+                credential_type: String::from(PUBLIC_KEY),
+                alg: COSE_ALGORITHM_ES256,
+            }],
+            timeout: Environment::webauthn_challenge_ttl_seconds() * 1000,
+            attestation: String::from("none"),
+            authenticator_selection: PasskeyAuthenticatorSelectionDto {
+                resident_key: String::from(REQUIRED),
+                user_verification: String::from(REQUIRED),
+            },
+            exclude_credentials: credentials
+                .into_iter()
+                .map(|credential| PasskeyCredentialDescriptorDto {
+                    credential_type: String::from(PUBLIC_KEY),
+                    id: credential.credential_id,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Only `none` attestation is asked for, so the authenticator model is not checked,
+    /// just that it holds the key and verified the user.
+    pub async fn register(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<PasskeyRegistrationDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<PasskeyDtoResponse>), AppError> {
+        let client_data_json = decode_base64url("clientDataJSON", &request.response.client_data_json)?;
+        let attestation_object = decode_base64url("attestationObject", &request.response.attestation_object)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let challenge = PasskeyChallenge::take(
+            &mut transaction,
+            &WebAuthn::client_challenge(&client_data_json)?,
+            PasskeyCeremony::Registration,
+        )
+        .await?
+        .filter(|challenge| challenge.customer_id == Some(claims.sub))
+        .ok_or_else(|| unknown_challenge(StatusCode::BAD_REQUEST))?;
+        let passkey = WebAuthn::from_env().verify_registration(&client_data_json, &attestation_object, &challenge.challenge)?;
+        let credential_id = BASE64URL_NOPAD.encode(&passkey.credential_id);
+        if credential_id != request.id.trim_end_matches('=') {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("Invalid passkey: credential id does not match"),
+                None,
+            )
+            .to_business_error());
+        }
+        let credential = PasskeyCredential::insert(
+            &mut transaction,
+            PasskeyCredential::new(claims.sub, credential_id, passkey.public_key, passkey.sign_count),
+        ).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok((
+            StatusCode::CREATED,
+            AppJsonResponse::new(PasskeyDtoResponse {
+                id: credential.id,
+                credential_id: credential.credential_id,
+            })
+        ))
+    }
+
+    pub async fn authentication_options(
+        State(app_state): State<AppState>,
+    ) -> Result<AppJsonResponse<PasskeyAuthenticationOptionsDtoResponse>, AppError> {
+        let web_authn = WebAuthn::from_env();
+        let mut transaction = app_state.begin_transaction().await?;
+        let challenge = PasskeyChallenge::insert(
+            &mut transaction,
+            PasskeyChallenge::new(PasskeyCeremony::Authentication, None),
+        ).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(PasskeyAuthenticationOptionsDtoResponse {
+            challenge: challenge.challenge,
+            rp_id: web_authn.rp_id().to_string(),
+            timeout: Environment::webauthn_challenge_ttl_seconds() * 1000,
+            user_verification: String::from(REQUIRED),
+            allow_credentials: vec![],
+        }))
+    }
+
+    /// Answers like `SingInUseCase::sing_in` does. The authenticator verified the user with a
+    /// PIN or biometrics, so the passkey already counts as two factors and MFA is not asked again.
+    pub async fn authenticate(
+        State(app_state): State<AppState>,
+        device: ClientDevice,
+        AppJsonRequest(request): AppJsonRequest<PasskeyAuthenticationDtoRequest>,
+    ) -> Result<Response, AppError> {
+        let client_data_json = decode_base64url("clientDataJSON", &request.response.client_data_json)?;
+        let authenticator_data = decode_base64url("authenticatorData", &request.response.authenticator_data)?;
+        let signature = decode_base64url("signature", &request.response.signature)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let credential = PasskeyCredential::find_by_credential_id(&mut transaction, request.id.trim_end_matches('='))
+            .await?
+            .ok_or_else(no_access)?;
+        if let Some(user_handle) = &request.response.user_handle {
+            if decode_base64url("userHandle", user_handle)? != credential.customer_id.as_bytes() {
+                return Err(no_access());
+            }
+        }
+        let challenge = PasskeyChallenge::take(
+            &mut transaction,
+            &WebAuthn::client_challenge(&client_data_json)?,
+            PasskeyCeremony::Authentication,
+        )
+        .await?
+        .ok_or_else(|| unknown_challenge(StatusCode::UNAUTHORIZED))?;
+        let sign_count = WebAuthn::from_env().verify_assertion(
+            &client_data_json,
+            &authenticator_data,
+            &signature,
+            &challenge.challenge,
+            &credential.public_key,
+            credential.stored_sign_count(),
+        )?;
+        PasskeyCredential::register_use(&mut transaction, credential.id, sign_count).await?;
+        let customer = Customer::get_by_id(&mut transaction, credential.customer_id).await?;
+        customer.ensure_verified()?;
+        let tokens = AuthTokens::start_session(&app_state, &mut transaction, &customer, device).await?;
+        app_state.commit_transaction(transaction).await?;
+        tokens.into_response(customer)
+    }
+}
+
+fn unknown_challenge(status: StatusCode) -> AppError {
+    AppErrorData::new(
+        status,
+        String::from("Invalid passkey: unknown or expired challenge"),
+        None,
+    )
+    .to_business_error()
+}
+
+fn no_access() -> AppError {
+    AppErrorData::new(
+        StatusCode::UNAUTHORIZED,
+        String::from("No access"),
+        None,
+    )
+    .to_business_error()
+}
@@ -0,0 +1,112 @@
+use axum::extract::State;
+use hyper::StatusCode;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{infra::{axum::AppJsonRequest, env::Environment, errors::{AppError, AppErrorData, FieldErrors}}, state::AppState, support::mail::MailMessage};
+
+use super::{domain::{Customer, CustomerSession, ForgotPasswordDtoRequest, PasswordResetToken, ResetPasswordDtoRequest, SignInAttempt}, policy::EmailPolicy, validators::Validator};
+
+pub struct PasswordUseCase;
+impl PasswordUseCase {
+    /// Answers right away and does the lookup in the background, so neither the response
+    /// nor its timing reveals whether the email is registered.
+    pub async fn forgot(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<ForgotPasswordDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let email = EmailPolicy::normalize(&request.email);
+        tokio::spawn(async move {
+            if let Err(err) = Self::send_reset_mail(&app_state, email).await {
+                error!("Failed to send password reset mail: {:?}", err);
+            }
+        });
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    /// Every session of the customer ends, including the ones of whoever may have taken over the account.
+    pub async fn reset(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<ResetPasswordDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        Validator::token_not_empty(&request.token)?;
+        Validator::password("password", &request.password)?;
+        // A bad token is refused before spending time on hashing, outside of any transaction.
+        let mut transaction = app_state.begin_transaction().await?;
+        PasswordResetToken::get_by(&mut transaction, &request.token).await?;
+        app_state.commit_transaction(transaction).await?;
+        let password_hashed = app_state.hashing_pool.encode(request.password).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let reset_token = PasswordResetToken::get_by(&mut transaction, &request.token).await?;
+        PasswordResetToken::consume(&mut transaction, &reset_token).await?;
+        Customer::update_password(&mut transaction, reset_token.customer_id, password_hashed).await?;
+        CustomerSession::revoke_all(&mut transaction, reset_token.customer_id).await?;
+        Customer::revoke_tokens(&mut transaction, reset_token.customer_id).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// Wrong passwords count as failed sign ins, so a stolen access token can not be used to guess it.
+    /// The hash is verified between two transactions, none is kept open while it runs.
+    pub async fn verify_current(
+        app_state: &AppState,
+        customer_id: Uuid,
+        password: String,
+        client_ip: &str
+    ) -> Result<Customer, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, customer_id).await?;
+        SignInAttempt::ensure_not_locked(&mut transaction, &customer.email, client_ip).await?;
+        app_state.commit_transaction(transaction).await?;
+        let password_matches = app_state.hashing_pool.verify(customer.password.clone(), password).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        if password_matches {
+            SignInAttempt::reset(&mut transaction, &customer.email).await?;
+        } else {
+            SignInAttempt::register_failure(&mut transaction, &customer.email, client_ip).await?;
+        }
+        app_state.commit_transaction(transaction).await?;
+        if !password_matches {
+            return Err(wrong_current_password());
+        }
+        Ok(customer)
+    }
+
+    /// A new link is only mailed once `PASSWORD_RESET_RESEND_SECONDS` passed since the last one.
+    async fn send_reset_mail(app_state: &AppState, email: String) -> Result<(), AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let Some(customer) = Customer::find_by(&mut transaction, email).await? else {
+            return Ok(());
+        };
+        let latest_reset_token = PasswordResetToken::find_latest(&mut transaction, customer.id).await?;
+        if latest_reset_token.is_some_and(|reset_token| reset_token.was_sent_recently()) {
+            return Ok(());
+        }
+        let (reset_token, plain_token) = PasswordResetToken::new(customer.id);
+        PasswordResetToken::insert(&mut transaction, reset_token).await?;
+        app_state.commit_transaction(transaction).await?;
+        app_state.mail_sender.send(MailMessage {
+            to: customer.email,
+            subject: String::from("Reset your password"),
+            body: format!(
+                "Choose a new password by opening the link below:\n\n{}?token={}\n\nIf you did not ask for it, ignore this mail.",
+                Environment::password_reset_url(),
+                plain_token
+            ),
+        }).await
+    }
+}
+
+fn wrong_current_password() -> AppError {
+    let errors = FieldErrors::from([(
+        String::from("currentPassword"),
+        vec![String::from("current password does not match")],
+    )]);
+    AppErrorData::new(
+        StatusCode::BAD_REQUEST,
+        String::from("invalid fields: currentPassword"),
+        None,
+    )
+    .with_errors(errors)
+    .to_business_error()
+}
@@ -0,0 +1,233 @@
+use email_address::EmailAddress;
+
+use crate::infra::env::Environment;
+
+/// Bcrypt silently ignores everything after the 72nd byte of a password.
+pub const BCRYPT_MAX_PASSWORD_BYTES: usize = 72;
+/// Same size as the `customer.email` column.
+pub const EMAIL_MAX_LENGTH: usize = 200;
+/// E.164 numbers have at most 15 digits, country code included.
+pub const PHONE_NUMBER_MAX_DIGITS: usize = 15;
+pub const PHONE_NUMBER_MIN_DIGITS: usize = 8;
+
+pub struct EmailPolicy;
+impl EmailPolicy {
+    pub fn normalize(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
+    /// Expects an already normalized email.
+    pub fn violations(email: &str) -> Vec<String> {
+        if email.is_empty() {
+            return vec![String::from("email is expected")];
+        }
+        let mut violations = Vec::new();
+        if email.len() > EMAIL_MAX_LENGTH {
+            violations.push(format!("email must have at most {} characters", EMAIL_MAX_LENGTH));
+        }
+        if !EmailAddress::is_valid(email) {
+            violations.push(String::from("email is not a valid address"));
+        }
+        violations
+    }
+}
+
+pub struct PhoneNumberPolicy;
+impl PhoneNumberPolicy {
+    /// Drops the separators people usually type, `+55 (11) 98765-4321` becomes `+5511987654321`.
+    pub fn normalize(phone_number: &str) -> String {
+        phone_number
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+            .collect()
+    }
+
+    /// Expects an already normalized phone number, which must be in E.164 format.
+    pub fn violations(phone_number: &str) -> Vec<String> {
+        if phone_number.is_empty() {
+            return vec![String::from("phone number is expected")];
+        }
+        let valid = phone_number
+            .strip_prefix('+')
+            .filter(|digits| (PHONE_NUMBER_MIN_DIGITS..=PHONE_NUMBER_MAX_DIGITS).contains(&digits.len()))
+            .filter(|digits| digits.chars().all(|c| c.is_ascii_digit()) && !digits.starts_with('0'))
+            .is_some();
+        if !valid {
+            return vec![String::from("phone number must be in E.164 format, like +5511987654321")];
+        }
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl PasswordPolicy {
+    /// The maximum length is capped to the bcrypt limit whatever is configured.
+    pub fn from_env() -> Self {
+        Self {
+            min_length: Environment::password_min_length() as usize,
+            max_length: (Environment::password_max_length() as usize).min(BCRYPT_MAX_PASSWORD_BYTES),
+            require_lowercase: Environment::password_require_lowercase(),
+            require_uppercase: Environment::password_require_uppercase(),
+            require_digit: Environment::password_require_digit(),
+            require_symbol: Environment::password_require_symbol(),
+        }
+    }
+
+    /// The minimum is counted in characters, the maximum in bytes because that is what bcrypt truncates.
+    pub fn violations(&self, password: &str) -> Vec<String> {
+        if password.is_empty() {
+            return vec![String::from("password is expected")];
+        }
+        let mut violations = Vec::new();
+        if password.chars().count() < self.min_length {
+            violations.push(format!("password must have at least {} characters", self.min_length));
+        }
+        if password.len() > self.max_length {
+            violations.push(format!("password must have at most {} bytes", self.max_length));
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(String::from("password must have a lowercase letter"));
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(String::from("password must have an uppercase letter"));
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(String::from("password must have a digit"));
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+            violations.push(String::from("password must have a symbol"));
+        }
+        violations
+    }
+}
+
+/// Failures are counted per email and per client IP. Reaching the maximum locks that key, and each
+/// further failure doubles the lockout, up to `max_lockout_seconds`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockoutPolicy {
+    pub max_failures_per_email: i32,
+    pub max_failures_per_ip: i32,
+    pub failure_window_seconds: i64,
+    pub base_lockout_seconds: i64,
+    pub max_lockout_seconds: i64,
+}
+
+impl LockoutPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            max_failures_per_email: Environment::sign_in_max_failures_per_email(),
+            max_failures_per_ip: Environment::sign_in_max_failures_per_ip(),
+            failure_window_seconds: Environment::sign_in_failure_window_seconds(),
+            base_lockout_seconds: Environment::sign_in_lockout_base_seconds(),
+            max_lockout_seconds: Environment::sign_in_lockout_max_seconds(),
+        }
+    }
+
+    pub fn lockout_seconds(&self, failures: i32, max_failures: i32) -> Option<i64> {
+        if failures < max_failures {
+            return None;
+        }
+        let exponent = u32::try_from(failures - max_failures).unwrap_or(0).min(30);
+        let lockout_seconds = self.base_lockout_seconds.saturating_mul(1_i64 << exponent);
+        Some(lockout_seconds.min(self.max_lockout_seconds))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            max_length: 64,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: true,
+        }
+    }
+
+    #[test]
+    fn should_normalize_email() {
+        assert_eq!(EmailPolicy::normalize("  User@FIAP.com.br "), "user@fiap.com.br");
+    }
+
+    #[test]
+    fn should_return_violations_when_email_is_invalid() {
+        assert!(EmailPolicy::violations("user@fiap.com.br").is_empty());
+        assert_eq!(EmailPolicy::violations(""), vec![String::from("email is expected")]);
+        assert_eq!(EmailPolicy::violations("a"), vec![String::from("email is not a valid address")]);
+        assert_eq!(EmailPolicy::violations("user@"), vec![String::from("email is not a valid address")]);
+    }
+
+    #[test]
+    fn should_normalize_phone_number() {
+        assert_eq!(PhoneNumberPolicy::normalize(" +55 (11) 98765-4321 "), "+5511987654321");
+    }
+
+    #[test]
+    fn should_return_violations_when_phone_number_is_not_e164() {
+        let format_violation = vec![String::from("phone number must be in E.164 format, like +5511987654321")];
+
+        assert!(PhoneNumberPolicy::violations("+5511987654321").is_empty());
+        assert_eq!(PhoneNumberPolicy::violations(""), vec![String::from("phone number is expected")]);
+        assert_eq!(PhoneNumberPolicy::violations("5511987654321"), format_violation);
+        assert_eq!(PhoneNumberPolicy::violations("+0511987654321"), format_violation);
+        assert_eq!(PhoneNumberPolicy::violations("+55119876a4321"), format_violation);
+        assert_eq!(PhoneNumberPolicy::violations("+1234567"), format_violation);
+        assert_eq!(PhoneNumberPolicy::violations("+1234567890123456"), format_violation);
+    }
+
+    #[test]
+    fn should_accept_password_following_policy() {
+        assert!(policy().violations("my$ecr3T").is_empty());
+    }
+
+    #[test]
+    fn should_return_every_violation_of_password() {
+        assert_eq!(
+            policy().violations("1"),
+            vec![
+                String::from("password must have at least 8 characters"),
+                String::from("password must have a lowercase letter"),
+                String::from("password must have an uppercase letter"),
+                String::from("password must have a symbol"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_count_max_length_in_bytes() {
+        let password = format!("aA1$ã{}", "a".repeat(59));
+
+        assert_eq!(password.chars().count(), 64);
+        assert_eq!(policy().violations(&password), vec![String::from("password must have at most 64 bytes")]);
+    }
+
+    #[test]
+    fn should_double_lockout_after_max_failures() {
+        let policy = LockoutPolicy {
+            max_failures_per_email: 3,
+            max_failures_per_ip: 10,
+            failure_window_seconds: 900,
+            base_lockout_seconds: 30,
+            max_lockout_seconds: 100,
+        };
+
+        assert_eq!(policy.lockout_seconds(2, 3), None);
+        assert_eq!(policy.lockout_seconds(3, 3), Some(30));
+        assert_eq!(policy.lockout_seconds(4, 3), Some(60));
+        assert_eq!(policy.lockout_seconds(5, 3), Some(100));
+        assert_eq!(policy.lockout_seconds(80, 3), Some(100));
+    }
+}
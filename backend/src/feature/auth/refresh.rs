@@ -0,0 +1,55 @@
+use axum::{extract::State, response::Response};
+use hyper::StatusCode;
+
+use crate::{feature::oauth::domain::OauthGrant, infra::{axum::{AppJsonRequest, ClientIp}, errors::{AppError, AppErrorData}}, state::AppState};
+
+use super::{domain::{Customer, CustomerSession, RefreshToken, RefreshTokenDtoRequest}, tokens::AuthTokens, validators::Validator};
+
+pub struct RefreshUseCase;
+impl RefreshUseCase {
+    pub async fn refresh(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AppJsonRequest(request): AppJsonRequest<RefreshTokenDtoRequest>
+    ) -> Result<Response, AppError> {
+        Validator::refresh_token_not_empty(&request)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let refresh_token = RefreshToken::get_by(&mut transaction, &request.refresh_token).await?;
+        // Refreshing here would drop the client and scope the tokens were bound to.
+        if OauthGrant::find_by_session(&mut transaction, refresh_token.family_id).await?.is_some() {
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Refresh token was issued to an OAuth client, use /oauth/token"),
+                None,
+            )
+            .to_business_error());
+        }
+        if refresh_token.was_used() {
+            // A rotated token showing up again means it leaked, so nobody holding this session can be trusted anymore.
+            RefreshToken::revoke_family(&mut transaction, refresh_token.family_id).await?;
+            CustomerSession::revoke(&mut transaction, refresh_token.customer_id, refresh_token.family_id).await?;
+            app_state.commit_transaction(transaction).await?;
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Refresh token reuse detected"),
+                None,
+            )
+            .to_business_error());
+        }
+        if refresh_token.is_expired() {
+            return Err(AppErrorData::new(
+                StatusCode::UNAUTHORIZED,
+                String::from("Refresh token expired"),
+                None,
+            )
+            .to_business_error());
+        }
+        RefreshToken::rotate(&mut transaction, refresh_token.id).await?;
+        CustomerSession::touch(&mut transaction, refresh_token.family_id, &client_ip).await?;
+        let customer = Customer::get_by_id(&mut transaction, refresh_token.customer_id).await?;
+        customer.ensure_verified()?;
+        let tokens = AuthTokens::issue(&app_state, &mut transaction, &customer, refresh_token.family_id).await?;
+        app_state.commit_transaction(transaction).await?;
+        tokens.into_response(customer)
+    }
+}
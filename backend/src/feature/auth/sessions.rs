@@ -0,0 +1,45 @@
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use uuid::Uuid;
+
+use crate::{infra::{axum::AppJsonResponse, errors::{AppError, AppErrorData}}, state::AppState};
+
+use super::{authorization::AuthorizedCustomer, domain::{CustomerSession, SessionDtoResponse}};
+
+pub struct SessionsUseCase;
+impl SessionsUseCase {
+    pub async fn list(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+    ) -> Result<AppJsonResponse<Vec<SessionDtoResponse>>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let sessions = CustomerSession::find_active(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(
+            sessions
+                .into_iter()
+                .map(|session| session.to_dto(claims.sid))
+                .collect()
+        ))
+    }
+
+    /// Signs the device out: its refresh token stops working right away and its access
+    /// token is refused from the next request on.
+    pub async fn revoke(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        Path(session_id): Path<Uuid>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        if !CustomerSession::revoke(&mut transaction, claims.sub, session_id).await? {
+            return Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("Session not found"),
+                None,
+            )
+            .to_business_error());
+        }
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -1,40 +1,77 @@
-use axum::{extract::State, http::HeaderValue, response::{IntoResponse, Response}};
-use hyper::{HeaderMap, StatusCode};
+use axum::{extract::State, response::{IntoResponse, Response}};
+use hyper::StatusCode;
+use sqlx::{Postgres, Transaction};
 
-use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState, support::{hash::hash_bcrypt::HashBCrypt, jwt::{AuthorizationClaims, Jwt}}};
+use crate::{infra::{axum::{AppJsonRequest, ClientDevice}, errors::{AppError, AppErrorData}}, state::AppState, support::hash::hash_password::HashPassword};
 
-use super::{domain::{Customer, CustomerDtoRequest, CustomerDtoResponse}, validators::Validator};
+use super::{domain::{Customer, CustomerDtoRequest, CustomerMfa, SignInAttempt}, mfa::MfaUseCase, policy::EmailPolicy, tokens::AuthTokens, validators::Validator};
 
 
 pub struct SingInUseCase;
 impl SingInUseCase {
     pub async fn sing_in(
         State(app_state): State<AppState>,
+        device: ClientDevice,
         AppJsonRequest(request): AppJsonRequest<CustomerDtoRequest>
     ) -> Result<Response, AppError> {
         Validator::email_and_password_not_empty(&request)?;
+        let email = EmailPolicy::normalize(&request.email);
         let mut transaction = app_state.begin_transaction().await?;
-        let customer = Customer::get_by(&mut transaction, request.email).await?;
+        SignInAttempt::ensure_not_locked(&mut transaction, &email, &device.ip).await?;
+        let customer = Customer::find_by(&mut transaction, email.clone()).await?;
+        // Hashing takes a while, no transaction is kept open meanwhile.
         app_state.commit_transaction(transaction).await?;
-        if HashBCrypt::verify(customer.password, request.password)? {
-            let claims = AuthorizationClaims::new(customer.id, customer.email.clone(), 30);
-            let jwt = AuthorizationClaims::generate_jwt(&claims, Environment::jwt_private_key())?;
-            let mut header = HeaderMap::new();
-            header.insert("Authorization", HeaderValue::from_str(&jwt).expect("Failed to insert header"));
-            Ok((
-                header,
-                AppJsonResponse::new(CustomerDtoResponse {
-                    id: customer.id,
-                    email: customer.email,
-                })
-            ).into_response())
-        } else {
-            Err(AppErrorData::new(
-                StatusCode::UNAUTHORIZED,
-                String::from("No access"),
-                None,
-            )
-            .to_business_error())
+        // Unknown emails go through a dummy verification and the same 401,
+        // so neither the response nor its timing tells which emails are registered.
+        let password_matches = match &customer {
+            Some(customer) => app_state.hashing_pool.verify(customer.password.clone(), request.password.clone()).await?,
+            None => app_state.hashing_pool.verify_dummy(request.password.clone()).await?,
+        };
+        match customer.filter(|_| password_matches) {
+            Some(customer) => {
+                customer.ensure_verified()?;
+                // Only now the plain password is at hand to move an old hash to the current algorithm and costs.
+                let password_rehashed = if HashPassword::needs_rehash(&customer.password)? {
+                    Some(app_state.hashing_pool.encode(request.password).await?)
+                } else {
+                    None
+                };
+                let mut transaction = app_state.begin_transaction().await?;
+                SignInAttempt::reset(&mut transaction, &email).await?;
+                if let Some(password_hashed) = password_rehashed {
+                    Customer::update_password(&mut transaction, customer.id, password_hashed).await?;
+                }
+                Self::complete(&app_state, transaction, customer, device).await
+            }
+            None => {
+                let mut transaction = app_state.begin_transaction().await?;
+                SignInAttempt::register_failure(&mut transaction, &email, &device.ip).await?;
+                app_state.commit_transaction(transaction).await?;
+                Err(AppErrorData::new(
+                    StatusCode::UNAUTHORIZED,
+                    String::from("No access"),
+                    None,
+                )
+                .to_business_error())
+            }
         }
     }
+
+    /// Finishes a sign in once the first factor is proven, committing the transaction.
+    /// With MFA enabled it only earns a challenge, the tokens then come from `/auth/mfa/verify`.
+    pub async fn complete(
+        app_state: &AppState,
+        mut transaction: Transaction<'_, Postgres>,
+        customer: Customer,
+        device: ClientDevice
+    ) -> Result<Response, AppError> {
+        let mfa = CustomerMfa::find_by(&mut transaction, customer.id).await?;
+        if mfa.is_some_and(|mfa| mfa.is_enabled()) {
+            app_state.commit_transaction(transaction).await?;
+            return Ok(MfaUseCase::challenge(app_state, &customer)?.into_response());
+        }
+        let tokens = AuthTokens::start_session(app_state, &mut transaction, &customer, device).await?;
+        app_state.commit_transaction(transaction).await?;
+        tokens.into_response(customer)
+    }
 }
@@ -1,6 +1,6 @@
 use axum::extract::State;
 use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::AppError}, state::AppState};
-use super::{domain::{Customer, CustomerDtoRequest, CustomerDtoResponse}, validators::Validator};
+use super::{domain::{Customer, CustomerDtoRequest, CustomerDtoResponse}, validators::Validator, verify_email::VerificationMail};
 
 pub struct SingUpUseCase;
 impl SingUpUseCase {
@@ -8,11 +8,14 @@ impl SingUpUseCase {
         State(app_state): State<AppState>,
         AppJsonRequest(request): AppJsonRequest<CustomerDtoRequest>,
     ) -> Result<AppJsonResponse<CustomerDtoResponse>, AppError>{
-        Validator::email_and_password_not_empty(&request)?;
+        let request = Validator::customer_credentials(request)?;
+        let password_hashed = app_state.hashing_pool.encode(request.password).await?;
         let mut transaction = app_state.begin_transaction().await?;
-        let mut customer = Customer::new(request.email, request.password)?;
+        let mut customer = Customer::new(request.email, password_hashed);
         customer = Customer::insert(&mut transaction, customer).await?;
+        let verification_mail = VerificationMail::prepare(&mut transaction, &customer).await?;
         app_state.commit_transaction(transaction).await?;
+        VerificationMail::send(&app_state, verification_mail).await;
         Ok(
             AppJsonResponse::new(CustomerDtoResponse {
                 id: customer.id,
@@ -0,0 +1,146 @@
+use axum::{extract::State, response::Response};
+use hyper::{header::{HeaderValue, RETRY_AFTER}, StatusCode};
+use tracing::error;
+
+use crate::{infra::{axum::{AppJsonRequest, ClientDevice, ClientIp}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState, support::sms::SmsMessage};
+
+use super::{authorization::AuthorizedCustomer, domain::{ChangePhoneNumberDtoRequest, ConfirmPhoneNumberDtoRequest, Customer, PhoneNumberDtoRequest, SmsOtp, VerifySmsOtpDtoRequest}, password::PasswordUseCase, sing_in::SingInUseCase, validators::Validator};
+
+pub struct SmsUseCase;
+impl SmsUseCase {
+    /// The number is only stored once the code texted to it comes back through `confirm_phone_number`.
+    pub async fn update_phone_number(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<ChangePhoneNumberDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let phone_number = Validator::phone_number("phoneNumber", &request.phone_number)?;
+        Validator::current_password_not_empty(&request.current_password)?;
+        let customer = PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        Customer::ensure_phone_number_available(&mut transaction, customer.id, &phone_number).await?;
+        let latest_otp = SmsOtp::find_latest_phone_change(&mut transaction, customer.id).await?;
+        if latest_otp.is_some_and(|otp| otp.was_sent_recently()) {
+            return Err(AppErrorData::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                String::from("A code was texted recently, wait before asking for another one"),
+                None,
+            )
+            .with_header(RETRY_AFTER, HeaderValue::from(Environment::sms_otp_resend_seconds()))
+            .to_business_error());
+        }
+        let (otp, plain_code) = SmsOtp::new(customer.id, Some(phone_number.clone()));
+        SmsOtp::insert(&mut transaction, otp).await?;
+        app_state.commit_transaction(transaction).await?;
+        app_state.sms_sender.send(SmsMessage {
+            to: phone_number,
+            body: format!("Your code to confirm this phone number is {}. Do not share it with anyone.", plain_code),
+        }).await?;
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    /// Every wrong code counts against the attempts of the code, like `verify` does.
+    pub async fn confirm_phone_number(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<ConfirmPhoneNumberDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        Validator::code_not_empty(&request.code)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let otp = SmsOtp::find_latest_phone_change(&mut transaction, claims.sub)
+            .await?
+            .filter(|otp| otp.is_usable())
+            .ok_or_else(invalid_code)?;
+        if !otp.matches(&request.code) {
+            // The attempt has to be stored even though the request fails.
+            SmsOtp::register_failed_attempt(&mut transaction, otp.id).await?;
+            app_state.commit_transaction(transaction).await?;
+            return Err(invalid_code());
+        }
+        SmsOtp::consume(&mut transaction, otp.id).await?;
+        let phone_number = otp.phone_number.ok_or_else(invalid_code)?;
+        Customer::update_phone_number(&mut transaction, claims.sub, phone_number).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// Answers right away and does the lookup in the background, so neither the response
+    /// nor its timing reveals whether the phone number is registered.
+    pub async fn request(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<PhoneNumberDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let phone_number = Validator::phone_number("phoneNumber", &request.phone_number)?;
+        tokio::spawn(async move {
+            if let Err(err) = Self::send_code(&app_state, phone_number).await {
+                error!("Failed to send sms code: {:?}", err);
+            }
+        });
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    /// Answers like `SingInUseCase::sing_in` does. Every wrong code counts against the attempts of the code.
+    pub async fn verify(
+        State(app_state): State<AppState>,
+        device: ClientDevice,
+        AppJsonRequest(request): AppJsonRequest<VerifySmsOtpDtoRequest>,
+    ) -> Result<Response, AppError> {
+        let phone_number = Validator::phone_number("phoneNumber", &request.phone_number)?;
+        Validator::code_not_empty(&request.code)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::find_by_phone_number(&mut transaction, &phone_number)
+            .await?
+            .ok_or_else(no_access)?;
+        let otp = SmsOtp::find_latest(&mut transaction, customer.id)
+            .await?
+            .filter(|otp| otp.is_usable())
+            .ok_or_else(no_access)?;
+        if !otp.matches(&request.code) {
+            // The attempt has to be stored even though the request fails.
+            SmsOtp::register_failed_attempt(&mut transaction, otp.id).await?;
+            app_state.commit_transaction(transaction).await?;
+            return Err(no_access());
+        }
+        SmsOtp::consume(&mut transaction, otp.id).await?;
+        customer.ensure_verified()?;
+        SingInUseCase::complete(&app_state, transaction, customer, device).await
+    }
+
+    /// A new code is only texted once `SMS_OTP_RESEND_SECONDS` passed since the last one.
+    async fn send_code(app_state: &AppState, phone_number: String) -> Result<(), AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let Some(customer) = Customer::find_by_phone_number(&mut transaction, &phone_number).await? else {
+            return Ok(());
+        };
+        let latest_otp = SmsOtp::find_latest(&mut transaction, customer.id).await?;
+        if latest_otp.is_some_and(|otp| otp.was_sent_recently()) {
+            return Ok(());
+        }
+        let (otp, plain_code) = SmsOtp::new(customer.id, None);
+        SmsOtp::insert(&mut transaction, otp).await?;
+        app_state.commit_transaction(transaction).await?;
+        app_state.sms_sender.send(SmsMessage {
+            to: phone_number,
+            body: format!("Your sign in code is {}. Do not share it with anyone.", plain_code),
+        }).await
+    }
+}
+
+fn no_access() -> AppError {
+    AppErrorData::new(
+        StatusCode::UNAUTHORIZED,
+        String::from("No access"),
+        None,
+    )
+    .to_business_error()
+}
+
+fn invalid_code() -> AppError {
+    AppErrorData::new(
+        StatusCode::BAD_REQUEST,
+        String::from("Invalid or expired code"),
+        None,
+    )
+    .to_business_error()
+}
@@ -0,0 +1,69 @@
+use axum::{http::HeaderValue, response::{IntoResponse, Response}};
+use hyper::HeaderMap;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{infra::{axum::{AppJsonResponse, ClientDevice}, env::Environment, errors::{AppError, ToBusinessError}}, state::AppState, support::jwt::{AuthorizationClaims, Jwt}};
+
+use super::domain::{Customer, CustomerDtoResponse, CustomerRole, CustomerSession, RefreshToken};
+
+pub const AUTHORIZATION_HEADER: &str = "Authorization";
+pub const REFRESH_TOKEN_HEADER: &str = "Refresh-Token";
+
+pub struct AuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+impl AuthTokens {
+    /// Records a new session for the device and issues its first tokens.
+    pub async fn start_session(
+        app_state: &AppState,
+        transaction: &mut Transaction<'_, Postgres>,
+        customer: &Customer,
+        device: ClientDevice
+    ) -> Result<Self, AppError> {
+        let session = CustomerSession::insert(transaction, CustomerSession::new(customer.id, device)).await?;
+        Self::issue(app_state, transaction, customer, session.id).await
+    }
+
+    /// Issues a new access token and a refresh token for the session, the refresh token
+    /// joins the family of the ones rotated before it. The access token carries the roles
+    /// the customer holds at this moment.
+    pub async fn issue(
+        app_state: &AppState,
+        transaction: &mut Transaction<'_, Postgres>,
+        customer: &Customer,
+        session_id: Uuid
+    ) -> Result<Self, AppError> {
+        let roles = CustomerRole::find_all(transaction, customer.id).await?;
+        let claims = AuthorizationClaims::new(customer.id, session_id, customer.email.clone(), roles, Environment::access_token_ttl_seconds());
+        let access_token = AuthorizationClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?;
+        let (refresh_token, plain_refresh_token) = RefreshToken::new(customer.id, session_id);
+        RefreshToken::insert(transaction, refresh_token).await?;
+        Ok(Self {
+            access_token,
+            refresh_token: plain_refresh_token,
+        })
+    }
+
+    pub fn into_response(self, customer: Customer) -> Result<Response, AppError> {
+        let mut header = HeaderMap::new();
+        header.insert(
+            AUTHORIZATION_HEADER,
+            HeaderValue::from_str(&self.access_token)
+                .map_err(|err| err.to_business_error("Failed to insert access token header", None))?
+        );
+        header.insert(
+            REFRESH_TOKEN_HEADER,
+            HeaderValue::from_str(&self.refresh_token)
+                .map_err(|err| err.to_business_error("Failed to insert refresh token header", None))?
+        );
+        Ok((
+            header,
+            AppJsonResponse::new(CustomerDtoResponse {
+                id: customer.id,
+                email: customer.email,
+            })
+        ).into_response())
+    }
+}
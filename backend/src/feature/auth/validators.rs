@@ -1,11 +1,49 @@
 use hyper::StatusCode;
 
-use crate::infra::errors::{AppError, AppErrorData};
+use crate::infra::errors::{AppError, AppErrorData, FieldErrors};
 
-use super::domain::CustomerDtoRequest;
+use super::{domain::{CustomerDtoRequest, RefreshTokenDtoRequest}, policy::{EmailPolicy, PasswordPolicy, PhoneNumberPolicy}};
 
 pub struct Validator;
 impl Validator {
+    /// Applies the email and password policies, returning the request with its email normalized.
+    pub fn customer_credentials(request: CustomerDtoRequest) -> Result<CustomerDtoRequest, AppError> {
+        let email = EmailPolicy::normalize(&request.email);
+        let mut errors = FieldErrors::new();
+        add_violations(&mut errors, "email", EmailPolicy::violations(&email));
+        add_violations(&mut errors, "password", PasswordPolicy::from_env().violations(&request.password));
+        to_result(errors)?;
+        Ok(CustomerDtoRequest {
+            email,
+            password: request.password,
+        })
+    }
+
+    /// Shared by every endpoint that sets a new password, `field` is the name used in the request body.
+    pub fn password(field: &str, password: &str) -> Result<(), AppError> {
+        let mut errors = FieldErrors::new();
+        add_violations(&mut errors, field, PasswordPolicy::from_env().violations(password));
+        to_result(errors)
+    }
+
+    /// Returns the email normalized, `field` is the name used in the request body.
+    pub fn email(field: &str, email: &str) -> Result<String, AppError> {
+        let email = EmailPolicy::normalize(email);
+        let mut errors = FieldErrors::new();
+        add_violations(&mut errors, field, EmailPolicy::violations(&email));
+        to_result(errors)?;
+        Ok(email)
+    }
+
+    /// Returns the phone number normalized, `field` is the name used in the request body.
+    pub fn phone_number(field: &str, phone_number: &str) -> Result<String, AppError> {
+        let phone_number = PhoneNumberPolicy::normalize(phone_number);
+        let mut errors = FieldErrors::new();
+        add_violations(&mut errors, field, PhoneNumberPolicy::violations(&phone_number));
+        to_result(errors)?;
+        Ok(phone_number)
+    }
+
     pub fn email_and_password_not_empty(request: &CustomerDtoRequest) -> Result<(), AppError> {
         if request.email.is_empty() || request.password.is_empty() {
             return Err(AppErrorData::new(
@@ -17,4 +55,72 @@ impl Validator {
         }
         Ok(())
     }
+
+    pub fn current_password_not_empty(current_password: &str) -> Result<(), AppError> {
+        if current_password.is_empty() {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                "current_password is expected".to_string(),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    pub fn token_not_empty(token: &str) -> Result<(), AppError> {
+        if token.trim().is_empty() {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                "token is expected".to_string(),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    pub fn code_not_empty(code: &str) -> Result<(), AppError> {
+        if code.trim().is_empty() {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                "code is expected".to_string(),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+
+    pub fn refresh_token_not_empty(request: &RefreshTokenDtoRequest) -> Result<(), AppError> {
+        if request.refresh_token.trim().is_empty() {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                "refresh_token is expected".to_string(),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+}
+
+fn add_violations(errors: &mut FieldErrors, field: &str, violations: Vec<String>) {
+    if !violations.is_empty() {
+        errors.insert(field.to_string(), violations);
+    }
+}
+
+fn to_result(errors: FieldErrors) -> Result<(), AppError> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let fields = errors.keys().cloned().collect::<Vec<String>>().join(", ");
+    Err(AppErrorData::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid fields: {}", fields),
+        None,
+    )
+    .with_errors(errors)
+    .to_business_error())
 }
@@ -0,0 +1,79 @@
+use axum::extract::State;
+use hyper::StatusCode;
+use sqlx::{Postgres, Transaction};
+use tracing::error;
+
+use crate::{infra::{axum::AppJsonRequest, env::Environment, errors::AppError}, state::AppState, support::mail::MailMessage};
+
+use super::{domain::{Customer, CustomerStatus, EmailVerificationToken, ResendVerificationDtoRequest, VerifyEmailDtoRequest}, policy::EmailPolicy, validators::Validator};
+
+pub struct VerifyEmailUseCase;
+impl VerifyEmailUseCase {
+    pub async fn verify_email(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<VerifyEmailDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        Validator::token_not_empty(&request.token)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let verification_token = EmailVerificationToken::get_by(&mut transaction, &request.token).await?;
+        EmailVerificationToken::consume(&mut transaction, &verification_token).await?;
+        match verification_token.email {
+            Some(email) => Customer::change_email(&mut transaction, verification_token.customer_id, email).await?,
+            None => Customer::verify_email(&mut transaction, verification_token.customer_id).await?,
+        }
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// Always accepted, so it can not be used to find out which emails are registered.
+    pub async fn resend(
+        State(app_state): State<AppState>,
+        AppJsonRequest(request): AppJsonRequest<ResendVerificationDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::find_by(&mut transaction, EmailPolicy::normalize(&request.email)).await?;
+        if let Some(customer) = customer.filter(|customer| customer.status == CustomerStatus::PendingVerification) {
+            let message = VerificationMail::prepare(&mut transaction, &customer).await?;
+            app_state.commit_transaction(transaction).await?;
+            VerificationMail::send(&app_state, message).await;
+        }
+        Ok(StatusCode::ACCEPTED)
+    }
+}
+
+pub struct VerificationMail;
+impl VerificationMail {
+    /// Stores a new token in the caller's transaction, the mail should only be sent once it commits.
+    pub async fn prepare(transaction: &mut Transaction<'_, Postgres>, customer: &Customer) -> Result<MailMessage, AppError> {
+        let (verification_token, plain_token) = EmailVerificationToken::new(customer.id, None);
+        EmailVerificationToken::insert(transaction, verification_token).await?;
+        Ok(Self::message(customer.email.clone(), plain_token))
+    }
+
+    /// Same as `prepare`, but the mail goes to the new address and the customer only moves
+    /// to it once the link is opened.
+    pub async fn prepare_email_change(transaction: &mut Transaction<'_, Postgres>, customer: &Customer, email: String) -> Result<MailMessage, AppError> {
+        let (verification_token, plain_token) = EmailVerificationToken::new(customer.id, Some(email.clone()));
+        EmailVerificationToken::insert(transaction, verification_token).await?;
+        Ok(Self::message(email, plain_token))
+    }
+
+    fn message(to: String, plain_token: String) -> MailMessage {
+        MailMessage {
+            to,
+            subject: String::from("Confirm your email"),
+            body: format!(
+                "Confirm your email by opening the link below:\n\n{}?token={}",
+                Environment::email_verification_url(),
+                plain_token
+            ),
+        }
+    }
+
+    /// Delivery failures are only logged, the customer can ask for another mail.
+    pub async fn send(app_state: &AppState, message: MailMessage) {
+        if let Err(err) = app_state.mail_sender.send(message).await {
+            error!("Failed to send verification mail: {:?}", err);
+        }
+    }
+}
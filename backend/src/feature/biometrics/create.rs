@@ -1,19 +1,24 @@
 use axum::extract::State;
 
-use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::AppError}, state::AppState};
+use crate::{feature::{auth::authorization::AuthorizedCustomer, customers::domain::{ConsentPurpose, CustomerConsent}}, infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::AppError}, state::AppState};
 
 use super::{domain::{BiometricDtoRequest, BiometricDtoResponse, Biometrics}, validators::Validator};
 
 pub struct CreateUseCase;
 impl CreateUseCase {
+    /// Sending the image is how the customer consents to its processing, the consent is recorded with it.
     pub async fn create(
         State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
         AppJsonRequest(request): AppJsonRequest<BiometricDtoRequest>
     ) -> Result<AppJsonResponse<BiometricDtoResponse>, AppError> {
         Validator::customer_id_and_image_not_empty(&request)?;
+        Validator::customer_is_owner(&claims, &request.customer_id)?;
+        Validator::image_path_is_owned(&request.customer_id, &request.image_path)?;
         let mut transaction = app_state.begin_transaction().await?;
         let mut biometric = Biometrics::new(request.customer_id, request.image_path);
         biometric = Biometrics::insert(&mut transaction, biometric).await?;
+        CustomerConsent::grant(&mut transaction, claims.sub, ConsentPurpose::Biometrics).await?;
         app_state.commit_transaction(transaction).await?;
         Ok(AppJsonResponse::new(BiometricDtoResponse::from(biometric)))
     }
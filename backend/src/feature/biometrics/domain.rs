@@ -1,3 +1,5 @@
+use std::path::{Component, Path};
+
 use chrono::{DateTime, SecondsFormat, Utc};
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -60,6 +62,15 @@ pub struct Biometrics {
     pub updated_at: DateTime<Utc>
 }
 impl Biometrics {
+    /// Images are stored under a folder per customer, named after its id. A path outside of it is
+    /// someone else's image, or no image of this storage at all.
+    pub fn is_image_of(customer_id: Uuid, image_path: &str) -> bool {
+        let Some(file) = image_path.strip_prefix(&format!("{}/", customer_id)) else {
+            return false;
+        };
+        !file.is_empty() && Path::new(file).components().all(|component| matches!(component, Component::Normal(_)))
+    }
+
     pub fn new(
         customer_id: Uuid,
         image_path: String,
@@ -89,6 +100,7 @@ impl Biometrics {
             .fetch_one(&mut **transaction)
             .await
             .map_err(|err| err.to_business_error("insert", None))?;
+        BiometricsStatusChange::record(transaction, stored_biometric.customer_id, stored_biometric.status).await?;
         Ok(stored_biometric)
     }
 
@@ -99,7 +111,7 @@ impl Biometrics {
         let query = r#"
             UPDATE biometrics
             SET image_path = $2,
-                status = $3
+                status = $3,
                 updated_at = $4
             WHERE customer_id = $1
             RETURNING *
@@ -112,6 +124,7 @@ impl Biometrics {
             .fetch_one(&mut **transaction)
             .await
             .map_err(|err| err.to_business_error("update", None))?;
+        BiometricsStatusChange::record(transaction, updated_biometric.customer_id, updated_biometric.status).await?;
         Ok(updated_biometric)
     }
 
@@ -143,4 +156,97 @@ impl Biometrics {
             Err(err) => Err(err.to_business_error("get by", None)),
         }
     }
+
+    pub async fn find_all_by_customer(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid
+    ) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM biometrics
+            WHERE customer_id = $1
+            ORDER BY created_at
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find all by customer", None))
+    }
+
+    /// Returns the removed rows so the images they point to can be deleted as well.
+    pub async fn delete_all(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid
+    ) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            DELETE FROM biometrics
+            WHERE customer_id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("delete all", None))
+    }
+}
+
+/// Every status a customer biometrics went through, appended on each insert and update.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct BiometricsStatusChange {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub status: BiometricsStatus,
+    pub changed_at: DateTime<Utc>
+}
+impl BiometricsStatusChange {
+    pub async fn record(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid,
+        status: BiometricsStatus
+    ) -> Result<(), AppError> {
+        let query = r#"
+            INSERT INTO biometrics_status_history
+                (id, customer_id, status)
+            VALUES
+                ($1, $2, $3)
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::now_v7())
+            .bind(customer_id)
+            .bind(status)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("record status change", None))?;
+        Ok(())
+    }
+
+    pub async fn find_all_by_customer(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid
+    ) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM biometrics_status_history
+            WHERE customer_id = $1
+            ORDER BY changed_at, id
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find status history", None))
+    }
+
+    pub async fn delete_all(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            DELETE FROM biometrics_status_history
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("delete status history", None))?;
+        Ok(())
+    }
 }
@@ -0,0 +1,40 @@
+use axum::{extract::{Query, State}, response::{IntoResponse, Response}};
+use hyper::{header::CONTENT_TYPE, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{infra::errors::{AppError, AppErrorData}, state::AppState, support::jwt::{ImageLinkClaims, Jwt}};
+
+use super::domain::Biometrics;
+
+#[derive(Serialize, Deserialize)]
+pub struct ImageLinkDtoRequest {
+    pub token: String,
+}
+
+pub struct ImageUseCase;
+impl ImageUseCase {
+    /// Serves the links handed out by the customer data export. The link itself is the
+    /// authorization, and it stops working as soon as the biometric is gone. Only images stored
+    /// under the folder of the customer are served, whatever path older rows point to.
+    pub async fn download(
+        State(app_state): State<AppState>,
+        Query(request): Query<ImageLinkDtoRequest>,
+    ) -> Result<Response, AppError> {
+        let claims = ImageLinkClaims::extract_jwt(request.token, &app_state.jwt_key_ring)?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let biometrics = Biometrics::find_all_by_customer(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        let owned = Biometrics::is_image_of(claims.sub, &claims.path)
+            && biometrics.iter().any(|biometric| biometric.image_path == claims.path);
+        if !owned {
+            return Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("Image not found"),
+                None,
+            )
+            .to_business_error());
+        }
+        let image = app_state.image_storage.read(&claims.path).await?;
+        Ok(([(CONTENT_TYPE, "application/octet-stream")], image).into_response())
+    }
+}
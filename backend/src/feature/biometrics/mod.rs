@@ -3,3 +3,4 @@ pub mod validators;
 pub mod domain;
 pub mod update;
 pub mod get_by;
+pub mod image;
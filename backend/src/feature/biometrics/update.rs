@@ -2,7 +2,7 @@ use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::AppError}, state::AppState};
+use crate::{feature::auth::authorization::AuthorizedCaller, infra::{axum::{AppJsonRequest, AppJsonResponse}, errors::AppError}, state::AppState};
 
 use super::{domain::{BiometricDtoRequest, BiometricDtoResponse, Biometrics, BiometricsStatus}, validators::Validator};
 
@@ -15,17 +15,31 @@ pub struct BiometricUpdateDtoRequest {
 
 pub struct UpdateUseCase;
 impl UpdateUseCase {
+    /// Reviewers change the status of any customer biometric. Customers only touch their own,
+    /// and a new image goes back to analysis whatever status it had. API keys with the
+    /// `biometrics_review` scope act as reviewers.
     pub async fn update(
         State(app_state): State<AppState>,
+        caller: AuthorizedCaller,
         AppJsonRequest(request): AppJsonRequest<BiometricUpdateDtoRequest>
     ) -> Result<AppJsonResponse<BiometricDtoResponse>, AppError> {
         Validator::customer_id_and_image_not_empty(&BiometricDtoRequest { customer_id: request.customer_id, image_path: request.image_path.clone() })?;
+        Validator::caller_can_update(&caller, &request.customer_id)?;
         let mut transaction = app_state.begin_transaction().await?;
         let mut biometric = Biometrics::get_by(&mut transaction, request.customer_id).await?;
+        Validator::update_is_allowed(&caller, &biometric, &request.image_path, request.status)?;
+        let image_replaced = biometric.image_path != request.image_path;
+        if image_replaced {
+            Validator::image_path_is_owned(&biometric.customer_id, &request.image_path)?;
+        }
+        biometric.status = if image_replaced && !caller.is_reviewer() {
+            BiometricsStatus::InAnalysis
+        } else {
+            request.status
+        };
         biometric.image_path = request.image_path;
-        biometric.status = request.status;
         biometric = Biometrics::update(&mut transaction, biometric).await?;
         app_state.commit_transaction(transaction).await?;
-        Ok(AppJsonResponse::new(BiometricDtoResponse::from(biometric)))
+        Ok(AppJsonResponse::new(BiometricDtoResponse::from(biometric)).with_tags(caller.tags()))
     }
 }
@@ -1,9 +1,9 @@
 use hyper::StatusCode;
 use uuid::Uuid;
 
-use crate::infra::errors::{AppError, AppErrorData};
+use crate::{feature::{admin::domain::ApiKeyScope, auth::{authorization::AuthorizedCaller, domain::Role}}, infra::errors::{AppError, AppErrorData}, support::jwt::AuthorizationClaims};
 
-use super::domain::BiometricDtoRequest;
+use super::domain::{BiometricDtoRequest, Biometrics, BiometricsStatus};
 
 pub struct Validator;
 impl Validator {
@@ -18,6 +18,17 @@ impl Validator {
         }
         Ok(())
     }
+    pub fn image_path_is_owned(customer_id: &Uuid, image_path: &str) -> Result<(), AppError> {
+        if !Biometrics::is_image_of(*customer_id, image_path) {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                format!("image_path must be under {}/", customer_id),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
     pub fn customer_id_not_empty(customer_id: &Uuid) -> Result<(), AppError> {
         if customer_id.is_nil() || customer_id.to_string().is_empty() {
             return Err(AppErrorData::new(
@@ -29,4 +40,57 @@ impl Validator {
         }
         Ok(())
     }
-}   
+    pub fn customer_is_owner(claims: &AuthorizationClaims, customer_id: &Uuid) -> Result<(), AppError> {
+        if claims.sub != *customer_id {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                "customer is not allowed to access this biometric".to_string(),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+    pub fn customer_is_owner_or_reviewer(claims: &AuthorizationClaims, customer_id: &Uuid) -> Result<(), AppError> {
+        if claims.has_role(Role::Reviewer) {
+            return Ok(());
+        }
+        Self::customer_is_owner(claims, customer_id)
+    }
+    /// API keys need `biometrics_read` to look at any customer biometric.
+    pub fn caller_can_read(caller: &AuthorizedCaller, customer_id: &Uuid) -> Result<(), AppError> {
+        match caller {
+            AuthorizedCaller::Customer(claims) => Self::customer_is_owner_or_reviewer(claims, customer_id),
+            AuthorizedCaller::ApiKey(api_key) => api_key.ensure_scope(ApiKeyScope::BiometricsRead),
+        }
+    }
+    /// API keys need `biometrics_review` to change any customer biometric.
+    pub fn caller_can_update(caller: &AuthorizedCaller, customer_id: &Uuid) -> Result<(), AppError> {
+        match caller {
+            AuthorizedCaller::Customer(claims) => Self::customer_is_owner_or_reviewer(claims, customer_id),
+            AuthorizedCaller::ApiKey(api_key) => api_key.ensure_scope(ApiKeyScope::BiometricsReview),
+        }
+    }
+    /// Customers may only send a new image, reviewers may only change the status of someone else's biometric.
+    pub fn update_is_allowed(caller: &AuthorizedCaller, biometric: &Biometrics, image_path: &str, status: BiometricsStatus) -> Result<(), AppError> {
+        let owns_biometric = caller.customer_id() == Some(biometric.customer_id);
+        let changes_status = biometric.status != status && status != BiometricsStatus::InAnalysis;
+        let message = if caller.is_reviewer() && !owns_biometric {
+            (biometric.image_path != image_path)
+                .then_some("only the customer is allowed to replace the biometric image")
+        } else if caller.is_reviewer() {
+            changes_status.then_some("reviewers are not allowed to change the status of their own biometric")
+        } else {
+            changes_status.then_some("only reviewers are allowed to change the biometric status")
+        };
+        if let Some(message) = message {
+            return Err(AppErrorData::new(
+                StatusCode::FORBIDDEN,
+                message.to_string(),
+                Some(caller.tags()),
+            )
+            .to_business_error());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{feature::{auth::domain::Customer, biometrics::domain::{Biometrics, BiometricsStatusChange}}, infra::{env::Environment, errors::AppError}, state::AppState, support::random::RandomToken};
+
+use super::domain::CustomerDeletion;
+
+/// Carries out the deletions whose grace period is over. Each one runs in a single transaction and
+/// every step can run twice, so a deletion that fails halfway is simply tried again later. Images are
+/// only deleted from the storage once that transaction is committed.
+pub struct DeletionWorker;
+impl DeletionWorker {
+    pub fn spawn(app_state: AppState) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(Environment::account_deletion_interval_seconds()));
+            loop {
+                interval.tick().await;
+                match Self::process_due(&app_state).await {
+                    Ok(0) => {}
+                    Ok(processed) => info!("Processed {} customer deletions", processed),
+                    Err(err) => error!("Failed to process customer deletions: {:?}", err),
+                }
+            }
+        });
+    }
+
+    /// Returns how many deletions were attempted, failed ones included.
+    pub async fn process_due(app_state: &AppState) -> Result<usize, AppError> {
+        let mut processed = 0;
+        while let Some(customer_id) = Self::process_next(app_state).await? {
+            processed += 1;
+            info!("Processed deletion of customer {}", customer_id);
+        }
+        Ok(processed)
+    }
+
+    async fn process_next(app_state: &AppState) -> Result<Option<Uuid>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let Some(deletion) = CustomerDeletion::next_due(&mut transaction).await? else {
+            app_state.commit_transaction(transaction).await?;
+            return Ok(None);
+        };
+        match Self::erase(app_state, &mut transaction, deletion.customer_id).await {
+            Ok(image_paths) => {
+                CustomerDeletion::complete(&mut transaction, deletion.customer_id).await?;
+                app_state.commit_transaction(transaction).await?;
+                Self::delete_images(app_state, &image_paths).await;
+            }
+            Err(err) => {
+                error!("Failed to delete customer {}: {:?}", deletion.customer_id, err);
+                drop(transaction);
+                let mut transaction = app_state.begin_transaction().await?;
+                CustomerDeletion::register_failure(&mut transaction, deletion.customer_id, format!("{:?}", err)).await?;
+                app_state.commit_transaction(transaction).await?;
+            }
+        }
+        Ok(Some(deletion.customer_id))
+    }
+
+    /// Only the customer id and dates are left behind, next to the deletion record itself. Returns the
+    /// images to delete, those of rows pointing outside of the customer folder belong to someone else.
+    async fn erase(
+        app_state: &AppState,
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid
+    ) -> Result<Vec<String>, AppError> {
+        let customer = Customer::get_by_id(transaction, customer_id).await?;
+        let image_paths = Biometrics::delete_all(transaction, customer_id)
+            .await?
+            .into_iter()
+            .map(|biometric| biometric.image_path)
+            .filter(|image_path| Biometrics::is_image_of(customer_id, image_path))
+            .collect();
+        BiometricsStatusChange::delete_all(transaction, customer_id).await?;
+        CustomerDeletion::purge_auth_data(transaction, customer_id, &customer.email).await?;
+        let password_hashed = app_state.hashing_pool.encode(RandomToken::generate()).await?;
+        Customer::anonymize(transaction, customer_id, password_hashed).await?;
+        Ok(image_paths)
+    }
+
+    /// The rows are gone already, so an image that fails to be deleted is only logged.
+    async fn delete_images(app_state: &AppState, image_paths: &[String]) {
+        for image_path in image_paths {
+            if let Err(err) = app_state.image_storage.delete(image_path).await {
+                error!("Failed to delete image {}: {:?}", image_path, err);
+            }
+        }
+    }
+}
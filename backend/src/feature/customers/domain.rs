@@ -0,0 +1,400 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{feature::{auth::domain::{Customer, CustomerSession, CustomerStatus}, biometrics::domain::{Biometrics, BiometricsStatus, BiometricsStatusChange}}, infra::{env::Environment, errors::{AppError, ToBusinessError}}};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerProfileDtoResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub phone_number: Option<String>,
+    pub created_at: String,
+    pub updated_at: Option<String>
+}
+impl CustomerProfileDtoResponse {
+    pub fn from(domain: Customer) -> Self {
+        Self {
+            id: domain.id,
+            email: domain.email,
+            email_verified: domain.status == CustomerStatus::Active,
+            name: domain.name,
+            phone_number: domain.phone_number,
+            created_at: format_timestamp(domain.created_at),
+            updated_at: domain.updated_at.map(format_timestamp),
+        }
+    }
+}
+
+/// Fields left out are kept as they are, an empty name clears it.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileDtoRequest {
+    pub name: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordDtoRequest {
+    pub current_password: String,
+    pub new_password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEmailDtoRequest {
+    pub email: String,
+    pub current_password: String
+}
+
+/// Everything held about the customer, as downloaded from `/customers/me/export`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerExportDtoResponse {
+    pub exported_at: String,
+    pub customer: CustomerExportDto,
+    pub biometrics: Vec<BiometricExportDto>,
+    pub sessions: Vec<SessionExportDto>,
+    pub consents: Vec<ConsentExportDto>
+}
+
+/// The customer row without the password hash.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerExportDto {
+    pub id: Uuid,
+    pub email: String,
+    pub status: CustomerStatus,
+    pub email_verified_at: Option<String>,
+    pub name: Option<String>,
+    pub phone_number: Option<String>,
+    pub created_at: String,
+    pub updated_at: Option<String>
+}
+impl CustomerExportDto {
+    pub fn from(domain: Customer) -> Self {
+        Self {
+            id: domain.id,
+            email: domain.email,
+            status: domain.status,
+            email_verified_at: domain.email_verified_at.map(format_timestamp),
+            name: domain.name,
+            phone_number: domain.phone_number,
+            created_at: format_timestamp(domain.created_at),
+            updated_at: domain.updated_at.map(format_timestamp),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricExportDto {
+    pub image_path: String,
+    /// Signed link to download the image, it expires after `IMAGE_LINK_TTL_SECONDS`.
+    pub image_url: String,
+    pub status: BiometricsStatus,
+    pub created_at: String,
+    pub updated_at: String,
+    pub status_history: Vec<BiometricStatusChangeDto>
+}
+impl BiometricExportDto {
+    pub fn from(domain: Biometrics, image_url: String, status_history: &[BiometricsStatusChange]) -> Self {
+        Self {
+            image_path: domain.image_path,
+            image_url,
+            status: domain.status,
+            created_at: format_timestamp(domain.created_at),
+            updated_at: format_timestamp(domain.updated_at),
+            status_history: status_history
+                .iter()
+                .map(|change| BiometricStatusChangeDto {
+                    status: change.status,
+                    changed_at: format_timestamp(change.changed_at),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricStatusChangeDto {
+    pub status: BiometricsStatus,
+    pub changed_at: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExportDto {
+    pub id: Uuid,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+    pub created_at: String,
+    pub last_seen_at: String,
+    pub revoked_at: Option<String>
+}
+impl SessionExportDto {
+    pub fn from(domain: CustomerSession) -> Self {
+        Self {
+            id: domain.id,
+            device_name: domain.device_name,
+            user_agent: domain.user_agent,
+            ip_address: domain.ip_address,
+            created_at: format_timestamp(domain.created_at),
+            last_seen_at: format_timestamp(domain.last_seen_at),
+            revoked_at: domain.revoked_at.map(format_timestamp),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentExportDto {
+    pub purpose: ConsentPurpose,
+    pub granted_at: String,
+    pub revoked_at: Option<String>
+}
+impl ConsentExportDto {
+    pub fn from(domain: CustomerConsent) -> Self {
+        Self {
+            purpose: domain.purpose,
+            granted_at: format_timestamp(domain.granted_at),
+            revoked_at: domain.revoked_at.map(format_timestamp),
+        }
+    }
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccountDtoRequest {
+    pub current_password: String
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerDeletionDtoResponse {
+    pub requested_at: String,
+    pub scheduled_for: String
+}
+impl CustomerDeletionDtoResponse {
+    pub fn from(domain: CustomerDeletion) -> Self {
+        Self {
+            requested_at: format_timestamp(domain.requested_at),
+            scheduled_for: format_timestamp(domain.scheduled_for),
+        }
+    }
+}
+
+/// A request to erase the customer. The row is kept once completed, as the record of when the
+/// erasure was asked for and carried out.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct CustomerDeletion {
+    pub customer_id: Uuid,
+    pub requested_at: DateTime<Utc>,
+    pub scheduled_for: DateTime<Utc>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>
+}
+impl CustomerDeletion {
+    /// Asking again while a deletion is pending keeps the date first scheduled.
+    pub async fn schedule(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Self, AppError> {
+        let grace_period = Duration::seconds(Environment::account_deletion_grace_period_seconds());
+        let query = r#"
+            INSERT INTO customer_deletion
+                (customer_id, scheduled_for)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (customer_id) DO UPDATE
+                SET scheduled_for = customer_deletion.scheduled_for
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .bind(Utc::now() + grace_period)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("schedule customer deletion", None))
+    }
+
+    /// Returns whether there was a pending deletion to cancel.
+    pub async fn cancel(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            DELETE FROM customer_deletion
+            WHERE customer_id = $1
+                AND completed_at IS NULL
+        "#;
+        let result = sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("cancel customer deletion", None))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Locks the deletion until the transaction ends, other workers skip it meanwhile.
+    pub async fn next_due(transaction: &mut Transaction<'_, Postgres>) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer_deletion
+            WHERE completed_at IS NULL
+                AND scheduled_for <= now()
+            ORDER BY scheduled_for
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        "#;
+        sqlx::query_as(query)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("next due customer deletion", None))
+    }
+
+    pub async fn complete(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_deletion
+            SET completed_at = now(),
+                last_error = NULL
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("complete customer deletion", None))?;
+        Ok(())
+    }
+
+    /// Pushes the deletion back so the next attempt waits `ACCOUNT_DELETION_RETRY_SECONDS`.
+    pub async fn register_failure(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid,
+        error: String
+    ) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_deletion
+            SET attempts = attempts + 1,
+                last_error = $2,
+                scheduled_for = $3
+            WHERE customer_id = $1
+        "#;
+        sqlx::query(query)
+            .bind(customer_id)
+            .bind(error)
+            .bind(Utc::now() + Duration::seconds(Environment::account_deletion_retry_seconds()))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("register customer deletion failure", None))?;
+        Ok(())
+    }
+
+    /// Removes everything tied to the customer sign in: tokens, sessions, second factors, roles and the
+    /// sign in attempts kept under the email.
+    pub async fn purge_auth_data(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid,
+        email: &str
+    ) -> Result<(), AppError> {
+        let tables = [
+            "oauth_grant",
+            "oauth_authorization_code",
+            "refresh_token",
+            "revoked_token",
+            "customer_session",
+            "customer_mfa",
+            "mfa_recovery_code",
+            "passkey_credential",
+            "passkey_challenge",
+            "sms_otp",
+            "email_verification_token",
+            "password_reset_token",
+            "consumed_magic_link",
+            "customer_role",
+            "customer_email_conflict",
+        ];
+        for table in tables {
+            sqlx::query(&format!("DELETE FROM {} WHERE customer_id = $1", table))
+                .bind(customer_id)
+                .execute(&mut **transaction)
+                .await
+                .map_err(|err| err.to_business_error("purge auth data", None))?;
+        }
+        let query = r#"
+            DELETE FROM sign_in_attempt
+            WHERE scope = 'email'
+                AND identifier = $1
+        "#;
+        sqlx::query(query)
+            .bind(email)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("purge sign in attempts", None))?;
+        Ok(())
+    }
+}
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "consent_purpose", rename_all = "snake_case")]
+pub enum ConsentPurpose {
+    /// Processing of the images sent to `/biometrics`, sensitive data under the LGPD.
+    Biometrics,
+}
+
+/// Kept after the account is deleted, as the proof the processing was consented to.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct CustomerConsent {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub purpose: ConsentPurpose,
+    pub granted_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>
+}
+impl CustomerConsent {
+    /// Nothing changes while a consent for the purpose is still in force.
+    pub async fn grant(
+        transaction: &mut Transaction<'_, Postgres>,
+        customer_id: Uuid,
+        purpose: ConsentPurpose
+    ) -> Result<(), AppError> {
+        let query = r#"
+            INSERT INTO customer_consent
+                (id, customer_id, purpose)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM customer_consent
+                WHERE customer_id = $2
+                    AND purpose = $3
+                    AND revoked_at IS NULL
+            )
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::now_v7())
+            .bind(customer_id)
+            .bind(purpose)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("grant consent", None))?;
+        Ok(())
+    }
+
+    pub async fn find_all_by_customer(transaction: &mut Transaction<'_, Postgres>, customer_id: Uuid) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM customer_consent
+            WHERE customer_id = $1
+            ORDER BY granted_at
+        "#;
+        sqlx::query_as(query)
+            .bind(customer_id)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find consents", None))
+    }
+}
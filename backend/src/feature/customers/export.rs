@@ -0,0 +1,46 @@
+use axum::{extract::State, response::{IntoResponse, Response}};
+use chrono::{SecondsFormat, Utc};
+use hyper::header::CONTENT_DISPOSITION;
+
+use crate::{feature::{auth::{authorization::AuthorizedCustomer, domain::{Customer, CustomerSession}}, biometrics::domain::{Biometrics, BiometricsStatusChange}}, infra::{axum::AppJsonResponse, env::Environment, errors::AppError}, state::AppState, support::jwt::{ImageLinkClaims, Jwt}};
+
+use super::domain::{BiometricExportDto, ConsentExportDto, CustomerConsent, CustomerExportDto, CustomerExportDtoResponse, SessionExportDto};
+
+pub struct ExportUseCase;
+impl ExportUseCase {
+    /// Answers with a single JSON file to download. The images are not embedded,
+    /// each biometric carries a signed link to its image instead.
+    pub async fn export(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+    ) -> Result<Response, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, claims.sub).await?;
+        let biometrics = Biometrics::find_all_by_customer(&mut transaction, customer.id).await?;
+        let status_history = BiometricsStatusChange::find_all_by_customer(&mut transaction, customer.id).await?;
+        let sessions = CustomerSession::find_all_by_customer(&mut transaction, customer.id).await?;
+        let consents = CustomerConsent::find_all_by_customer(&mut transaction, customer.id).await?;
+        app_state.commit_transaction(transaction).await?;
+
+        let mut exported_biometrics = Vec::with_capacity(biometrics.len());
+        for biometric in biometrics {
+            let image_url = image_link(&app_state, &customer, &biometric.image_path)?;
+            exported_biometrics.push(BiometricExportDto::from(biometric, image_url, &status_history));
+        }
+        let file_name = format!("attachment; filename=\"customer-{}.json\"", customer.id);
+        let export = CustomerExportDtoResponse {
+            exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            customer: CustomerExportDto::from(customer),
+            biometrics: exported_biometrics,
+            sessions: sessions.into_iter().map(SessionExportDto::from).collect(),
+            consents: consents.into_iter().map(ConsentExportDto::from).collect(),
+        };
+        Ok(([(CONTENT_DISPOSITION, file_name)], AppJsonResponse::new(export)).into_response())
+    }
+}
+
+fn image_link(app_state: &AppState, customer: &Customer, image_path: &str) -> Result<String, AppError> {
+    let claims = ImageLinkClaims::new(customer.id, image_path.to_string(), Environment::image_link_ttl_seconds());
+    let token = ImageLinkClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?;
+    Ok(format!("{}?token={}", Environment::image_link_url(), token))
+}
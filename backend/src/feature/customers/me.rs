@@ -0,0 +1,136 @@
+use axum::extract::State;
+use hyper::StatusCode;
+use tracing::error;
+
+use crate::{feature::auth::{authorization::AuthorizedCustomer, domain::{Customer, CustomerSession}, password::PasswordUseCase, validators::Validator as AuthValidator, verify_email::VerificationMail}, infra::{axum::{AppJsonRequest, AppJsonResponse, ClientIp}, errors::{AppError, AppErrorData}}, state::AppState, support::mail::MailMessage};
+
+use super::{domain::{ChangeEmailDtoRequest, ChangePasswordDtoRequest, CustomerDeletion, CustomerDeletionDtoResponse, CustomerProfileDtoResponse, DeleteAccountDtoRequest, UpdateProfileDtoRequest}, validators::Validator};
+
+pub struct MeUseCase;
+impl MeUseCase {
+    pub async fn get(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+    ) -> Result<AppJsonResponse<CustomerProfileDtoResponse>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(CustomerProfileDtoResponse::from(customer)))
+    }
+
+    pub async fn update(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<UpdateProfileDtoRequest>,
+    ) -> Result<AppJsonResponse<CustomerProfileDtoResponse>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = match request.name {
+            Some(name) => Customer::update_profile(&mut transaction, claims.sub, Validator::name(&name)?).await?,
+            None => Customer::get_by_id(&mut transaction, claims.sub).await?,
+        };
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(CustomerProfileDtoResponse::from(customer)))
+    }
+
+    /// Every other session of the customer ends, the one making the request stays signed in.
+    pub async fn change_password(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<ChangePasswordDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        AuthValidator::current_password_not_empty(&request.current_password)?;
+        AuthValidator::password("newPassword", &request.new_password)?;
+        let password_hashed = app_state.hashing_pool.encode(request.new_password).await?;
+        let customer = PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        Customer::update_password(&mut transaction, customer.id, password_hashed).await?;
+        CustomerSession::revoke_others(&mut transaction, customer.id, claims.sid).await?;
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// The customer keeps the current address until the link mailed to the new one is opened,
+    /// the current address is told about the change in case someone else asked for it.
+    pub async fn change_email(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<ChangeEmailDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let email = AuthValidator::email("email", &request.email)?;
+        AuthValidator::current_password_not_empty(&request.current_password)?;
+        let customer = PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        if customer.email == email {
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("email is already the current one"),
+                None,
+            )
+            .to_business_error());
+        }
+        let mut transaction = app_state.begin_transaction().await?;
+        Customer::ensure_email_available(&mut transaction, customer.id, &email).await?;
+        let verification_mail = VerificationMail::prepare_email_change(&mut transaction, &customer, email.clone()).await?;
+        app_state.commit_transaction(transaction).await?;
+        VerificationMail::send(&app_state, verification_mail).await;
+        let notice = MailMessage {
+            to: customer.email,
+            subject: String::from("Your email is being changed"),
+            body: format!(
+                "A change of your account email to {} was requested. It only happens once the new address is confirmed.\n\nIf you did not ask for it, change your password.",
+                email
+            ),
+        };
+        if let Err(err) = app_state.mail_sender.send(notice).await {
+            error!("Failed to send email change notice: {:?}", err);
+        }
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    /// The account is only erased once `ACCOUNT_DELETION_GRACE_PERIOD_SECONDS` are over,
+    /// until then the customer can still sign in and cancel it.
+    pub async fn delete(
+        State(app_state): State<AppState>,
+        ClientIp(client_ip): ClientIp,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<DeleteAccountDtoRequest>,
+    ) -> Result<(StatusCode, AppJsonResponse<CustomerDeletionDtoResponse>), AppError> {
+        AuthValidator::current_password_not_empty(&request.current_password)?;
+        let customer = PasswordUseCase::verify_current(&app_state, claims.sub, request.current_password, &client_ip).await?;
+        let mut transaction = app_state.begin_transaction().await?;
+        let deletion = CustomerDeletion::schedule(&mut transaction, customer.id).await?;
+        app_state.commit_transaction(transaction).await?;
+        let notice = MailMessage {
+            to: customer.email,
+            subject: String::from("Your account will be deleted"),
+            body: format!(
+                "Your account and biometric data will be deleted on {}.\n\nUntil then you can sign in and cancel it.",
+                deletion.scheduled_for.to_rfc2822()
+            ),
+        };
+        if let Err(err) = app_state.mail_sender.send(notice).await {
+            error!("Failed to send account deletion notice: {:?}", err);
+        }
+        Ok((StatusCode::ACCEPTED, AppJsonResponse::new(CustomerDeletionDtoResponse::from(deletion))))
+    }
+
+    pub async fn cancel_deletion(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let cancelled = CustomerDeletion::cancel(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        if !cancelled {
+            return Err(AppErrorData::new(
+                StatusCode::NOT_FOUND,
+                String::from("No account deletion pending"),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+}
@@ -0,0 +1,5 @@
+pub mod deletion;
+pub mod domain;
+pub mod export;
+pub mod me;
+pub mod validators;
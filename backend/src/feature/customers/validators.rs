@@ -0,0 +1,28 @@
+use hyper::StatusCode;
+
+use crate::infra::errors::{AppError, AppErrorData, FieldErrors};
+
+/// Same size as the `customer.name` column.
+pub const NAME_MAX_LENGTH: usize = 200;
+
+pub struct Validator;
+impl Validator {
+    /// Returns the name trimmed, or `None` when it is blank.
+    pub fn name(name: &str) -> Result<Option<String>, AppError> {
+        let name = name.trim();
+        if name.chars().count() > NAME_MAX_LENGTH {
+            let errors = FieldErrors::from([(
+                String::from("name"),
+                vec![format!("name must have at most {} characters", NAME_MAX_LENGTH)],
+            )]);
+            return Err(AppErrorData::new(
+                StatusCode::BAD_REQUEST,
+                String::from("invalid fields: name"),
+                None,
+            )
+            .with_errors(errors)
+            .to_business_error());
+        }
+        Ok(Some(name.to_string()).filter(|name| !name.is_empty()))
+    }
+}
@@ -1,2 +1,5 @@
+pub mod admin;
 pub mod auth;
 pub mod biometrics;
+pub mod customers;
+pub mod oauth;
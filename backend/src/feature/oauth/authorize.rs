@@ -0,0 +1,114 @@
+use axum::{extract::{Query, State}, response::{IntoResponse, Response}};
+use hyper::{header::LOCATION, StatusCode};
+use sqlx::{Postgres, Transaction};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{feature::auth::authorization::AuthorizedCustomer, infra::{axum::{AppJsonRequest, AppJsonResponse}, env::Environment, errors::{AppError, AppErrorData}}, state::AppState};
+
+use super::domain::{oauth_error, AuthorizeDtoRequest, AuthorizeDtoResponse, OauthAuthorizationCode, OauthClient, OauthGrantType, CODE_CHALLENGE_METHOD};
+
+pub struct AuthorizeUseCase;
+impl AuthorizeUseCase {
+    /// Entry point of the browser, which can not carry the bearer token of the customer. Valid requests
+    /// go on to the login page of this service with their parameters, it signs the customer in, with MFA
+    /// or passkeys when needed, and continues with `POST /oauth/authorize`.
+    ///
+    /// Until the client and its redirect URI are known errors are answered here, afterwards they go back
+    /// to the client on its redirect URI as RFC 6749 asks.
+    pub async fn authorize(
+        State(app_state): State<AppState>,
+        Query(request): Query<AuthorizeDtoRequest>,
+    ) -> Result<Response, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let (client, redirect_uri) = Self::client_and_redirect_uri(&mut transaction, &request).await?;
+        app_state.commit_transaction(transaction).await?;
+        let location = match Self::validate(&client, &request) {
+            Ok(_) => append_params(&Environment::oauth_login_url(), &request.params())?,
+            Err((code, description)) => redirect_url(&redirect_uri, &[("error", code), ("error_description", description)], &request.state)?,
+        };
+        Ok((StatusCode::FOUND, [(LOCATION, location)]).into_response())
+    }
+
+    /// Called by the login page with the parameters it received, once the customer is signed in.
+    /// Clients are registered by admins, so no consent is asked. The page sends the browser on to
+    /// `redirectTo`, which carries either the code or the error.
+    pub async fn approve(
+        State(app_state): State<AppState>,
+        AuthorizedCustomer(claims): AuthorizedCustomer,
+        AppJsonRequest(request): AppJsonRequest<AuthorizeDtoRequest>,
+    ) -> Result<AppJsonResponse<AuthorizeDtoResponse>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let (client, redirect_uri) = Self::client_and_redirect_uri(&mut transaction, &request).await?;
+        let redirect_to = match Self::validate(&client, &request) {
+            Ok((code_challenge, scope)) => {
+                let (code, plain_code) = OauthAuthorizationCode::new(client.id, claims.sub, redirect_uri.clone(), scope, code_challenge, request.nonce.clone());
+                OauthAuthorizationCode::insert(&mut transaction, code).await?;
+                redirect_url(&redirect_uri, &[("code", &plain_code)], &request.state)?
+            }
+            Err((code, description)) => redirect_url(&redirect_uri, &[("error", code), ("error_description", description)], &request.state)?,
+        };
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(AuthorizeDtoResponse { redirect_to }))
+    }
+
+    async fn client_and_redirect_uri(
+        transaction: &mut Transaction<'_, Postgres>,
+        request: &AuthorizeDtoRequest
+    ) -> Result<(OauthClient, String), AppError> {
+        let client_id = request
+            .client_id
+            .as_deref()
+            .and_then(|client_id| Uuid::parse_str(client_id).ok())
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "client_id is expected"))?;
+        let client = OauthClient::find_active(transaction, client_id)
+            .await?
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "Unknown client"))?;
+        let redirect_uri = request
+            .redirect_uri
+            .clone()
+            .filter(|redirect_uri| client.allows_redirect_uri(redirect_uri))
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "redirect_uri is not registered for this client"))?;
+        Ok((client, redirect_uri))
+    }
+
+    /// Returns the code challenge and the granted scope, or the error code and description to redirect with.
+    fn validate(client: &OauthClient, request: &AuthorizeDtoRequest) -> Result<(String, String), (&'static str, &'static str)> {
+        if request.response_type.as_deref() != Some("code") {
+            return Err(("unsupported_response_type", "only the code response type is supported"));
+        }
+        if !client.allows_grant(OauthGrantType::AuthorizationCode) {
+            return Err(("unauthorized_client", "the client is not allowed to use the authorization code grant"));
+        }
+        let code_challenge = request
+            .code_challenge
+            .clone()
+            .filter(|code_challenge| !code_challenge.trim().is_empty() && request.code_challenge_method.as_deref() == Some(CODE_CHALLENGE_METHOD))
+            .ok_or(("invalid_request", "code_challenge with the S256 code_challenge_method is expected"))?;
+        let scope = client
+            .granted_scope(request.scope.as_deref())
+            .ok_or(("invalid_scope", "the scope is not allowed for this client"))?;
+        Ok((code_challenge, scope))
+    }
+}
+
+fn redirect_url(redirect_uri: &str, params: &[(&str, &str)], state: &Option<String>) -> Result<String, AppError> {
+    let mut params = params.to_vec();
+    if let Some(state) = state {
+        params.push(("state", state));
+    }
+    append_params(redirect_uri, &params)
+}
+
+fn append_params(url: &str, params: &[(&str, &str)]) -> Result<String, AppError> {
+    let mut url = Url::parse(url).map_err(|err| {
+        AppErrorData::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid redirect URL {}: {}", url, err),
+            None,
+        )
+        .to_business_error()
+    })?;
+    url.query_pairs_mut().extend_pairs(params);
+    Ok(url.to_string())
+}
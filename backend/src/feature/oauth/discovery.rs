@@ -0,0 +1,40 @@
+use axum::extract::State;
+
+use crate::{infra::{axum::AppJsonResponse, env::Environment, errors::AppError}, state::AppState};
+
+use super::domain::{OauthGrantType, OpenIdConfigurationDtoResponse, CODE_CHALLENGE_METHOD, SUPPORTED_SCOPES};
+
+pub struct DiscoveryUseCase;
+impl DiscoveryUseCase {
+    /// OpenID Connect discovery, served at `/.well-known/openid-configuration`.
+    pub async fn openid_configuration(
+        State(app_state): State<AppState>,
+    ) -> Result<AppJsonResponse<OpenIdConfigurationDtoResponse>, AppError> {
+        let issuer = Environment::oauth_issuer();
+        let signing_algorithm = app_state.jwt_key_ring.signing_key()?.algorithm;
+        Ok(AppJsonResponse::new(OpenIdConfigurationDtoResponse {
+            authorization_endpoint: format!("{}/oauth/authorize", issuer),
+            token_endpoint: format!("{}/oauth/token", issuer),
+            userinfo_endpoint: format!("{}/oauth/userinfo", issuer),
+            introspection_endpoint: format!("{}/oauth/introspect", issuer),
+            revocation_endpoint: format!("{}/oauth/revoke", issuer),
+            jwks_uri: format!("{}/.well-known/jwks.json", issuer),
+            issuer,
+            response_types_supported: vec![String::from("code")],
+            grant_types_supported: vec![
+                OauthGrantType::AuthorizationCode,
+                OauthGrantType::RefreshToken,
+                OauthGrantType::ClientCredentials,
+            ],
+            subject_types_supported: vec![String::from("public")],
+            id_token_signing_alg_values_supported: vec![format!("{:?}", signing_algorithm)],
+            scopes_supported: SUPPORTED_SCOPES.iter().map(|scope| scope.to_string()).collect(),
+            token_endpoint_auth_methods_supported: vec![
+                String::from("client_secret_basic"),
+                String::from("client_secret_post"),
+                String::from("none"),
+            ],
+            code_challenge_methods_supported: vec![String::from(CODE_CHALLENGE_METHOD)],
+        }))
+    }
+}
@@ -0,0 +1,673 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use data_encoding::{BASE64, BASE64URL_NOPAD};
+use hyper::{header::AUTHORIZATION, HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::{PgHasArrayType, PgTypeInfo}, prelude::FromRow, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{feature::auth::{authorization::AuthorizedCustomer, domain::{CustomerSession, RefreshToken}}, infra::{env::Environment, errors::{AppError, AppErrorData, ToBusinessError}}, support::{hash::hash_sha256::HashSha256, jwt::{AuthorizationClaims, ClientAccessClaims, Jwt, RevocableClaims}, jwt_keys::JwtKeyRing, random::RandomToken}};
+
+pub const SUPPORTED_SCOPES: [&str; 3] = ["openid", "profile", "email"];
+pub const CODE_CHALLENGE_METHOD: &str = "S256";
+pub const BEARER_TOKEN_TYPE: &str = "Bearer";
+const BASIC_PREFIX: &str = "Basic ";
+
+/// Errors of OAuth endpoints are answered with the body of RFC 6749, `code` being its `error`.
+pub fn oauth_error(status: StatusCode, code: &'static str, message: &str) -> AppError {
+    AppErrorData::new(status, message.to_string(), None)
+        .with_oauth_error(code)
+        .to_business_error()
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOauthClientDtoRequest {
+    pub name: String,
+    #[serde(default)]
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<OauthGrantType>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Public clients get no secret, which rules out the client credentials grant.
+    #[serde(default)]
+    pub public: bool
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OauthClientDtoResponse {
+    pub client_id: Uuid,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<OauthGrantType>,
+    pub scopes: Vec<String>,
+    pub public: bool,
+    pub revoked_at: Option<String>,
+    pub created_at: String
+}
+impl OauthClientDtoResponse {
+    pub fn from(domain: OauthClient) -> Self {
+        Self {
+            client_id: domain.id,
+            public: domain.is_public(),
+            name: domain.name,
+            redirect_uris: domain.redirect_uris,
+            grant_types: domain.grant_types,
+            scopes: domain.scopes,
+            revoked_at: domain.revoked_at.map(format_timestamp),
+            created_at: format_timestamp(domain.created_at),
+        }
+    }
+}
+
+/// The only time the plain secret is shown, it can not be recovered afterwards.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedOauthClientDtoResponse {
+    pub client_secret: Option<String>,
+    #[serde(flatten)]
+    pub client: OauthClientDtoResponse
+}
+
+/// Query of `GET /oauth/authorize`, forwarded by the login page as body of `POST /oauth/authorize`.
+/// Everything is optional so missing parameters are reported the OAuth way instead of being rejected
+/// by the extractor.
+#[derive(Serialize, Deserialize)]
+pub struct AuthorizeDtoRequest {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>
+}
+impl AuthorizeDtoRequest {
+    pub fn params(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("response_type", &self.response_type),
+            ("client_id", &self.client_id),
+            ("redirect_uri", &self.redirect_uri),
+            ("scope", &self.scope),
+            ("state", &self.state),
+            ("code_challenge", &self.code_challenge),
+            ("code_challenge_method", &self.code_challenge_method),
+            ("nonce", &self.nonce),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+        .collect()
+    }
+}
+
+/// The client redirect URI the login page sends the browser to, carrying either the code or the error.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizeDtoResponse {
+    pub redirect_to: String
+}
+
+/// Form of `/oauth/token`, the parameters expected depend on the `grant_type`.
+#[derive(Serialize, Deserialize)]
+pub struct TokenDtoRequest {
+    pub grant_type: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TokenDtoResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserInfoDtoResponse {
+    pub sub: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>
+}
+
+/// Form of `/oauth/introspect` and `/oauth/revoke`. The `token_type_hint` is accepted but not needed,
+/// every kind of token is tried.
+#[derive(Serialize, Deserialize)]
+pub struct TokenHintDtoRequest {
+    pub token: Option<String>,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>
+}
+
+/// Inactive tokens are answered with `active` alone, as RFC 7662 asks.
+#[derive(Serialize, Deserialize, Default)]
+pub struct IntrospectionDtoResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OpenIdConfigurationDtoResponse {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub jwks_uri: String,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<OauthGrantType>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub scopes_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>
+}
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "oauth_grant_type", rename_all = "snake_case")]
+pub enum OauthGrantType {
+    AuthorizationCode,
+    RefreshToken,
+    ClientCredentials,
+}
+
+impl PgHasArrayType for OauthGrantType {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_oauth_grant_type")
+    }
+}
+
+/// Application registered by an admin to sign customers in through `/oauth/authorize`.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct OauthClient {
+    pub id: Uuid,
+    pub name: String,
+    pub secret_hash: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<OauthGrantType>,
+    pub scopes: Vec<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl OauthClient {
+    /// Returns the client to store together with its plain secret, which is never persisted.
+    /// Public clients have none.
+    pub fn new(
+        name: String,
+        redirect_uris: Vec<String>,
+        grant_types: Vec<OauthGrantType>,
+        scopes: Vec<String>,
+        public: bool
+    ) -> (Self, Option<String>) {
+        let plain_secret = (!public).then(RandomToken::generate);
+        let client = Self {
+            id: Uuid::now_v7(),
+            name,
+            secret_hash: plain_secret.as_deref().map(HashSha256::encode),
+            redirect_uris,
+            grant_types,
+            scopes,
+            revoked_at: None,
+            created_at: Utc::now(),
+        };
+        (client, plain_secret)
+    }
+
+    /// Clients authenticate with HTTP Basic or with `client_id` and `client_secret` in the form,
+    /// public clients with their `client_id` alone.
+    pub async fn authenticate(
+        transaction: &mut Transaction<'_, Postgres>,
+        headers: &HeaderMap,
+        client_id: Option<String>,
+        client_secret: Option<String>
+    ) -> Result<Self, AppError> {
+        let (client_id, client_secret) = match basic_credentials(headers) {
+            Some((client_id, client_secret)) => (Some(client_id), Some(client_secret)),
+            None => (client_id, client_secret),
+        };
+        let invalid_client = || oauth_error(StatusCode::UNAUTHORIZED, "invalid_client", "Client authentication failed");
+        let client_id = client_id
+            .as_deref()
+            .and_then(|client_id| Uuid::parse_str(client_id).ok())
+            .ok_or_else(invalid_client)?;
+        Self::find_active(transaction, client_id)
+            .await?
+            .filter(|client| client.authenticates_with(client_secret.as_deref()))
+            .ok_or_else(invalid_client)
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.secret_hash.is_none()
+    }
+
+    /// Public clients authenticate with their id alone, confidential ones also need their secret.
+    pub fn authenticates_with(&self, secret: Option<&str>) -> bool {
+        match (&self.secret_hash, secret) {
+            (None, None) => true,
+            (Some(secret_hash), Some(secret)) => *secret_hash == HashSha256::encode(secret),
+            _ => false,
+        }
+    }
+
+    pub fn allows_grant(&self, grant_type: OauthGrantType) -> bool {
+        self.grant_types.contains(&grant_type)
+    }
+
+    /// Redirect URIs are compared as registered, without any normalization.
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|registered| registered == redirect_uri)
+    }
+
+    /// Without a requested scope the client gets every scope it was registered with.
+    /// Returns `None` when a requested scope was not registered.
+    pub fn granted_scope(&self, requested: Option<&str>) -> Option<String> {
+        let Some(requested) = requested.filter(|requested| !requested.trim().is_empty()) else {
+            return Some(self.scopes.join(" "));
+        };
+        let mut scopes: Vec<&str> = Vec::new();
+        for scope in requested.split_whitespace() {
+            if !self.scopes.iter().any(|registered| registered == scope) {
+                return None;
+            }
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        Some(scopes.join(" "))
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, client: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO oauth_client
+                (id, name, secret_hash, redirect_uris, grant_types, scopes)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(client.id)
+            .bind(client.name)
+            .bind(client.secret_hash)
+            .bind(client.redirect_uris)
+            .bind(client.grant_types)
+            .bind(client.scopes)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert oauth client", None))
+    }
+
+    pub async fn find_all(transaction: &mut Transaction<'_, Postgres>) -> Result<Vec<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM oauth_client
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as(query)
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find oauth clients", None))
+    }
+
+    pub async fn find_active(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM oauth_client
+            WHERE id = $1
+                AND revoked_at IS NULL
+        "#;
+        sqlx::query_as(query)
+            .bind(id)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find active oauth client", None))
+    }
+
+    /// Also ends every session granted to the client. Returns whether there was a client left to revoke.
+    pub async fn revoke(transaction: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool, AppError> {
+        let query = r#"
+            UPDATE oauth_client
+            SET revoked_at = now()
+            WHERE id = $1
+                AND revoked_at IS NULL
+        "#;
+        let result = sqlx::query(query)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke oauth client", None))?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        OauthGrant::revoke_all_by_client(transaction, id).await?;
+        Ok(true)
+    }
+}
+
+fn basic_credentials(headers: &HeaderMap) -> Option<(String, String)> {
+    let encoded = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().strip_prefix(BASIC_PREFIX))?;
+    let decoded = BASE64.decode(encoded.trim().as_bytes()).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (client_id, client_secret) = decoded.split_once(':')?;
+    Some((client_id.to_string(), client_secret.to_string()))
+}
+
+/// Single use code handed to the client on its redirect URI, exchanged at `/oauth/token`.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct OauthAuthorizationCode {
+    pub id: Uuid,
+    pub code_hash: String,
+    pub client_id: Uuid,
+    pub customer_id: Uuid,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+    pub session_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+impl OauthAuthorizationCode {
+    /// Returns the code to store together with the plain value handed to the client,
+    /// which is never persisted.
+    pub fn new(
+        client_id: Uuid,
+        customer_id: Uuid,
+        redirect_uri: String,
+        scope: String,
+        code_challenge: String,
+        nonce: Option<String>
+    ) -> (Self, String) {
+        let plain_code = RandomToken::generate();
+        let code = Self {
+            id: Uuid::now_v7(),
+            code_hash: HashSha256::encode(&plain_code),
+            client_id,
+            customer_id,
+            redirect_uri,
+            scope,
+            code_challenge,
+            nonce,
+            session_id: None,
+            expires_at: Utc::now() + Duration::seconds(Environment::oauth_authorization_code_ttl_seconds()),
+            consumed_at: None,
+            created_at: Utc::now(),
+        };
+        (code, plain_code)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// PKCE with `S256`, the only method accepted by `/oauth/authorize`.
+    pub fn verifies(&self, code_verifier: &str) -> bool {
+        BASE64URL_NOPAD.encode(&Sha256::digest(code_verifier.as_bytes())) == self.code_challenge
+    }
+
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, code: Self) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO oauth_authorization_code
+                (id, code_hash, client_id, customer_id, redirect_uri, scope, code_challenge, nonce, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(code.id)
+            .bind(code.code_hash)
+            .bind(code.client_id)
+            .bind(code.customer_id)
+            .bind(code.redirect_uri)
+            .bind(code.scope)
+            .bind(code.code_challenge)
+            .bind(code.nonce)
+            .bind(code.expires_at)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert oauth authorization code", None))
+    }
+
+    pub async fn find_by(transaction: &mut Transaction<'_, Postgres>, plain_code: &str) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM oauth_authorization_code
+            WHERE code_hash = $1
+            FOR UPDATE
+        "#;
+        sqlx::query_as(query)
+            .bind(HashSha256::encode(plain_code))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find oauth authorization code", None))
+    }
+
+    /// Remembers the session the code started, so a replay of the code can end it.
+    pub async fn consume(transaction: &mut Transaction<'_, Postgres>, id: Uuid, session_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE oauth_authorization_code
+            SET consumed_at = now(), session_id = $2
+            WHERE id = $1
+        "#;
+        sqlx::query(query)
+            .bind(id)
+            .bind(session_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("consume oauth authorization code", None))?;
+        Ok(())
+    }
+}
+
+/// What a customer granted to a client, one per session started through `/oauth/token`.
+/// Refresh tokens of these sessions are only accepted from that client.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct OauthGrant {
+    pub session_id: Uuid,
+    pub client_id: Uuid,
+    pub customer_id: Uuid,
+    pub scope: String,
+    pub created_at: DateTime<Utc>
+}
+impl OauthGrant {
+    pub async fn insert(
+        transaction: &mut Transaction<'_, Postgres>,
+        session_id: Uuid,
+        client_id: Uuid,
+        customer_id: Uuid,
+        scope: &str
+    ) -> Result<Self, AppError> {
+        let query = r#"
+            INSERT INTO oauth_grant
+                (session_id, client_id, customer_id, scope)
+            VALUES
+                ($1, $2, $3, $4)
+            RETURNING *
+        "#;
+        sqlx::query_as(query)
+            .bind(session_id)
+            .bind(client_id)
+            .bind(customer_id)
+            .bind(scope)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert oauth grant", None))
+    }
+
+    pub async fn find_by_session(transaction: &mut Transaction<'_, Postgres>, session_id: Uuid) -> Result<Option<Self>, AppError> {
+        let query = r#"
+            SELECT * FROM oauth_grant
+            WHERE session_id = $1
+        "#;
+        sqlx::query_as(query)
+            .bind(session_id)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("find oauth grant", None))
+    }
+
+    /// Ends the sessions granted to the client together with their refresh tokens.
+    pub async fn revoke_all_by_client(transaction: &mut Transaction<'_, Postgres>, client_id: Uuid) -> Result<(), AppError> {
+        let query = r#"
+            UPDATE customer_session
+            SET revoked_at = now()
+            WHERE id IN (SELECT session_id FROM oauth_grant WHERE client_id = $1)
+                AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(client_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke oauth client sessions", None))?;
+        let query = r#"
+            UPDATE refresh_token
+            SET revoked_at = now()
+            WHERE family_id IN (SELECT session_id FROM oauth_grant WHERE client_id = $1)
+                AND revoked_at IS NULL
+        "#;
+        sqlx::query(query)
+            .bind(client_id)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("revoke oauth client refresh tokens", None))?;
+        Ok(())
+    }
+}
+
+/// Denylist of the client credentials tokens, which have a client instead of a customer as subject.
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct RevokedClientToken {
+    pub jti: Uuid,
+    pub client_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+impl RevokedClientToken {
+    pub fn new(claims: &ClientAccessClaims) -> Self {
+        Self {
+            jti: claims.jti(),
+            client_id: claims.sub(),
+            expires_at: claims.expires_at(),
+            created_at: Utc::now()
+        }
+    }
+
+    /// Entries are only useful until the token they deny expires, so expired ones are purged on every insert.
+    pub async fn insert(transaction: &mut Transaction<'_, Postgres>, revoked_token: Self) -> Result<(), AppError> {
+        let purge_query = r#"
+            DELETE FROM revoked_client_token
+            WHERE expires_at < now()
+        "#;
+        sqlx::query(purge_query)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("purge revoked client tokens", None))?;
+        let query = r#"
+            INSERT INTO revoked_client_token
+                (jti, client_id, expires_at)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+        "#;
+        sqlx::query(query)
+            .bind(revoked_token.jti)
+            .bind(revoked_token.client_id)
+            .bind(revoked_token.expires_at)
+            .execute(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("insert revoked client token", None))?;
+        Ok(())
+    }
+
+    pub async fn is_revoked(transaction: &mut Transaction<'_, Postgres>, claims: &ClientAccessClaims) -> Result<bool, AppError> {
+        let query = r#"
+            SELECT EXISTS (
+                SELECT 1 FROM revoked_client_token
+                WHERE jti = $1
+                AND expires_at >= now()
+            )
+        "#;
+        sqlx::query_scalar(query)
+            .bind(claims.jti)
+            .fetch_one(&mut **transaction)
+            .await
+            .map_err(|err| err.to_business_error("check revoked client token", None))
+    }
+}
+
+/// Token presented to `/oauth/introspect` or `/oauth/revoke`. Tokens with an invalid signature or
+/// already expired, and refresh tokens of sessions not started through `/oauth/token`, are unknown.
+pub enum OauthToken {
+    Access(AuthorizationClaims),
+    ClientAccess(ClientAccessClaims),
+    Refresh(RefreshToken, OauthGrant)
+}
+impl OauthToken {
+    pub async fn find(transaction: &mut Transaction<'_, Postgres>, key_ring: &JwtKeyRing, plain_token: &str) -> Result<Option<Self>, AppError> {
+        if let Ok(claims) = AuthorizationClaims::extract_jwt(plain_token.to_string(), key_ring) {
+            return Ok(Some(Self::Access(claims)));
+        }
+        if let Ok(claims) = ClientAccessClaims::extract_jwt(plain_token.to_string(), key_ring) {
+            return Ok(Some(Self::ClientAccess(claims)));
+        }
+        let Some(refresh_token) = RefreshToken::find_by(transaction, plain_token).await? else {
+            return Ok(None);
+        };
+        let grant = OauthGrant::find_by_session(transaction, refresh_token.family_id).await?;
+        Ok(grant.map(|grant| Self::Refresh(refresh_token, grant)))
+    }
+
+    /// Client the token was issued to, none for the access tokens of `/auth/singin`.
+    pub fn client_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Access(claims) => claims.client_id,
+            Self::ClientAccess(claims) => Some(claims.sub),
+            Self::Refresh(_, grant) => Some(grant.client_id),
+        }
+    }
+
+    /// Access tokens go through the same revocation checks as `AuthorizedCustomer`.
+    pub async fn is_active(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<bool, AppError> {
+        match self {
+            Self::Access(claims) => Ok(!AuthorizedCustomer::is_revoked(transaction, claims).await?),
+            Self::ClientAccess(claims) => Ok(!RevokedClientToken::is_revoked(transaction, claims).await?
+                && OauthClient::find_active(transaction, claims.sub).await?.is_some()),
+            Self::Refresh(refresh_token, _) => Ok(!refresh_token.was_used()
+                && !refresh_token.is_expired()
+                && !CustomerSession::is_revoked(transaction, refresh_token.family_id).await?),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use axum::extract::State;
+use hyper::{HeaderMap, StatusCode};
+
+use crate::{infra::{axum::{AppFormRequest, AppJsonResponse}, errors::AppError}, state::AppState};
+
+use super::domain::{oauth_error, IntrospectionDtoResponse, OauthClient, OauthToken, TokenHintDtoRequest, BEARER_TOKEN_TYPE};
+
+const REFRESH_TOKEN_TYPE: &str = "refresh_token";
+
+pub struct IntrospectUseCase;
+impl IntrospectUseCase {
+    /// Any confidential client may introspect, resource servers included, so the answer does not
+    /// depend on the client the token was issued to. Public clients are refused, their id is no secret.
+    pub async fn introspect(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        AppFormRequest(request): AppFormRequest<TokenHintDtoRequest>,
+    ) -> Result<AppJsonResponse<IntrospectionDtoResponse>, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let client = OauthClient::authenticate(&mut transaction, &headers, request.client_id, request.client_secret).await?;
+        if client.is_public() {
+            return Err(oauth_error(StatusCode::UNAUTHORIZED, "invalid_client", "Public clients are not allowed to introspect tokens"));
+        }
+        let plain_token = request
+            .token
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "token is expected"))?;
+        let token = OauthToken::find(&mut transaction, &app_state.jwt_key_ring, &plain_token).await?;
+        let response = match token {
+            Some(token) if token.is_active(&mut transaction).await? => Self::describe(token),
+            _ => IntrospectionDtoResponse::default(),
+        };
+        app_state.commit_transaction(transaction).await?;
+        Ok(AppJsonResponse::new(response))
+    }
+
+    fn describe(token: OauthToken) -> IntrospectionDtoResponse {
+        let client_id = token.client_id();
+        let (scope, token_type, sub, iat, exp) = match token {
+            OauthToken::Access(claims) => (claims.scope, BEARER_TOKEN_TYPE, claims.sub, claims.iat as i64, claims.exp as i64),
+            OauthToken::ClientAccess(claims) => (Some(claims.scope), BEARER_TOKEN_TYPE, claims.sub, claims.iat as i64, claims.exp as i64),
+            OauthToken::Refresh(refresh_token, grant) => (
+                Some(grant.scope),
+                REFRESH_TOKEN_TYPE,
+                refresh_token.customer_id,
+                refresh_token.created_at.timestamp(),
+                refresh_token.expires_at.timestamp(),
+            ),
+        };
+        IntrospectionDtoResponse {
+            active: true,
+            scope,
+            client_id,
+            token_type: Some(String::from(token_type)),
+            sub: Some(sub),
+            exp: Some(exp),
+            iat: Some(iat),
+        }
+    }
+}
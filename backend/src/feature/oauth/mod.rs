@@ -0,0 +1,7 @@
+pub mod authorize;
+pub mod discovery;
+pub mod domain;
+pub mod introspect;
+pub mod revoke;
+pub mod token;
+pub mod userinfo;
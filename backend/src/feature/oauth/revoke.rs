@@ -0,0 +1,38 @@
+use axum::extract::State;
+use hyper::{HeaderMap, StatusCode};
+
+use crate::{feature::auth::domain::{CustomerSession, RevokedToken}, infra::{axum::AppFormRequest, errors::AppError}, state::AppState};
+
+use super::domain::{oauth_error, OauthClient, OauthToken, RevokedClientToken, TokenHintDtoRequest};
+
+pub struct RevokeUseCase;
+impl RevokeUseCase {
+    /// Unknown, expired and already revoked tokens are answered as revoked, as RFC 7009 asks.
+    /// Revoking a refresh token ends its session, so the access tokens of that session go with it.
+    pub async fn revoke(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        AppFormRequest(request): AppFormRequest<TokenHintDtoRequest>,
+    ) -> Result<StatusCode, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let client = OauthClient::authenticate(&mut transaction, &headers, request.client_id, request.client_secret).await?;
+        let plain_token = request
+            .token
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "token is expected"))?;
+        let Some(token) = OauthToken::find(&mut transaction, &app_state.jwt_key_ring, &plain_token).await? else {
+            return Ok(StatusCode::OK);
+        };
+        if token.client_id() != Some(client.id) {
+            return Err(oauth_error(StatusCode::BAD_REQUEST, "unauthorized_client", "The token was not issued to this client"));
+        }
+        match token {
+            OauthToken::Access(claims) => RevokedToken::insert(&mut transaction, RevokedToken::new(&claims)).await?,
+            OauthToken::ClientAccess(claims) => RevokedClientToken::insert(&mut transaction, RevokedClientToken::new(&claims)).await?,
+            OauthToken::Refresh(refresh_token, _) => {
+                CustomerSession::revoke(&mut transaction, refresh_token.customer_id, refresh_token.family_id).await?;
+            }
+        }
+        app_state.commit_transaction(transaction).await?;
+        Ok(StatusCode::OK)
+    }
+}
@@ -0,0 +1,184 @@
+use axum::{extract::State, response::{IntoResponse, Response}};
+use hyper::{header::{CACHE_CONTROL, PRAGMA}, HeaderMap, StatusCode};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{feature::auth::domain::{Customer, CustomerSession, RefreshToken}, infra::{axum::{AppFormRequest, AppJsonResponse, ClientDevice}, env::Environment, errors::AppError}, state::AppState, support::jwt::{AuthorizationClaims, ClientAccessClaims, IdTokenClaims, Jwt}};
+
+use super::domain::{oauth_error, OauthAuthorizationCode, OauthClient, OauthGrant, OauthGrantType, TokenDtoRequest, TokenDtoResponse, BEARER_TOKEN_TYPE};
+
+pub struct TokenUseCase;
+impl TokenUseCase {
+    pub async fn token(
+        State(app_state): State<AppState>,
+        device: ClientDevice,
+        headers: HeaderMap,
+        AppFormRequest(request): AppFormRequest<TokenDtoRequest>,
+    ) -> Result<Response, AppError> {
+        let mut transaction = app_state.begin_transaction().await?;
+        let client = OauthClient::authenticate(&mut transaction, &headers, request.client_id.clone(), request.client_secret.clone()).await?;
+        let grant_type = match request.grant_type.as_deref() {
+            Some("authorization_code") => OauthGrantType::AuthorizationCode,
+            Some("refresh_token") => OauthGrantType::RefreshToken,
+            Some("client_credentials") => OauthGrantType::ClientCredentials,
+            Some(_) => return Err(oauth_error(StatusCode::BAD_REQUEST, "unsupported_grant_type", "Unsupported grant_type")),
+            None => return Err(oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "grant_type is expected")),
+        };
+        if !client.allows_grant(grant_type) {
+            return Err(oauth_error(StatusCode::BAD_REQUEST, "unauthorized_client", "The client is not allowed to use this grant_type"));
+        }
+        let response = match grant_type {
+            OauthGrantType::AuthorizationCode => Self::exchange_code(&app_state, &mut transaction, &client, device, request).await,
+            OauthGrantType::RefreshToken => Self::refresh(&app_state, &mut transaction, &client, device, request).await,
+            OauthGrantType::ClientCredentials => Self::client_credentials(&app_state, &client, request),
+        };
+        // A replayed code or refresh token ends the session it started, which has to be stored even though the request fails.
+        app_state.commit_transaction(transaction).await?;
+        let headers = [(CACHE_CONTROL, "no-store"), (PRAGMA, "no-cache")];
+        Ok((headers, AppJsonResponse::new(response?)).into_response())
+    }
+
+    async fn exchange_code(
+        app_state: &AppState,
+        transaction: &mut Transaction<'_, Postgres>,
+        client: &OauthClient,
+        device: ClientDevice,
+        request: TokenDtoRequest
+    ) -> Result<TokenDtoResponse, AppError> {
+        let (Some(plain_code), Some(redirect_uri), Some(code_verifier)) = (request.code, request.redirect_uri, request.code_verifier) else {
+            return Err(oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "code, redirect_uri and code_verifier are expected"));
+        };
+        let code = OauthAuthorizationCode::find_by(transaction, &plain_code)
+            .await?
+            .filter(|code| code.client_id == client.id)
+            .ok_or_else(|| invalid_grant("Invalid authorization code"))?;
+        if code.consumed_at.is_some() {
+            if let Some(session_id) = code.session_id {
+                CustomerSession::revoke(transaction, code.customer_id, session_id).await?;
+            }
+            return Err(invalid_grant("Authorization code already used"));
+        }
+        if code.is_expired() {
+            return Err(invalid_grant("Authorization code expired"));
+        }
+        if code.redirect_uri != redirect_uri || !code.verifies(&code_verifier) {
+            return Err(invalid_grant("Invalid authorization code"));
+        }
+        let customer = Customer::get_by_id(transaction, code.customer_id).await?;
+        customer.ensure_verified()?;
+        let device = ClientDevice {
+            device_name: Some(client.name.clone()),
+            ..device
+        };
+        let session = CustomerSession::insert(transaction, CustomerSession::new(customer.id, device)).await?;
+        OauthAuthorizationCode::consume(transaction, code.id, session.id).await?;
+        OauthGrant::insert(transaction, session.id, client.id, customer.id, &code.scope).await?;
+        issue(app_state, transaction, client, &customer, session.id, code.scope, code.nonce).await
+    }
+
+    /// Rotates like `/auth/refresh` does, only for refresh tokens issued to the same client.
+    /// The scope can be narrowed, never widened.
+    async fn refresh(
+        app_state: &AppState,
+        transaction: &mut Transaction<'_, Postgres>,
+        client: &OauthClient,
+        device: ClientDevice,
+        request: TokenDtoRequest
+    ) -> Result<TokenDtoResponse, AppError> {
+        let Some(plain_refresh_token) = request.refresh_token else {
+            return Err(oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "refresh_token is expected"));
+        };
+        let refresh_token = RefreshToken::find_by(transaction, &plain_refresh_token)
+            .await?
+            .ok_or_else(|| invalid_grant("Invalid refresh token"))?;
+        let grant = OauthGrant::find_by_session(transaction, refresh_token.family_id)
+            .await?
+            .filter(|grant| grant.client_id == client.id)
+            .ok_or_else(|| invalid_grant("Invalid refresh token"))?;
+        if refresh_token.was_used() {
+            RefreshToken::revoke_family(transaction, refresh_token.family_id).await?;
+            CustomerSession::revoke(transaction, refresh_token.customer_id, refresh_token.family_id).await?;
+            return Err(invalid_grant("Refresh token reuse detected"));
+        }
+        if refresh_token.is_expired() {
+            return Err(invalid_grant("Refresh token expired"));
+        }
+        let scope = match request.scope.as_deref().filter(|scope| !scope.trim().is_empty()) {
+            Some(requested) if requested.split_whitespace().all(|scope| grant.scope.split(' ').any(|granted| granted == scope)) => requested.to_string(),
+            Some(_) => return Err(oauth_error(StatusCode::BAD_REQUEST, "invalid_scope", "The scope exceeds the one granted")),
+            None => grant.scope,
+        };
+        RefreshToken::rotate(transaction, refresh_token.id).await?;
+        CustomerSession::touch(transaction, refresh_token.family_id, &device.ip).await?;
+        let customer = Customer::get_by_id(transaction, refresh_token.customer_id).await?;
+        customer.ensure_verified()?;
+        issue(app_state, transaction, client, &customer, refresh_token.family_id, scope, None).await
+    }
+
+    /// The client acts on its own behalf, so there is no refresh token nor ID token.
+    fn client_credentials(app_state: &AppState, client: &OauthClient, request: TokenDtoRequest) -> Result<TokenDtoResponse, AppError> {
+        let scope = client
+            .granted_scope(request.scope.as_deref())
+            .ok_or_else(|| oauth_error(StatusCode::BAD_REQUEST, "invalid_scope", "The scope is not allowed for this client"))?;
+        let expires_in = Environment::access_token_ttl_seconds();
+        let claims = ClientAccessClaims::new(Environment::oauth_issuer(), client.id, scope.clone(), expires_in);
+        Ok(TokenDtoResponse {
+            access_token: ClientAccessClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?,
+            token_type: String::from(BEARER_TOKEN_TYPE),
+            expires_in,
+            scope,
+            refresh_token: None,
+            id_token: None,
+        })
+    }
+}
+
+/// The access token is the one of `/auth/singin` bound to the client and its scope, without roles since
+/// first-party routes refuse it anyway. The refresh token comes only when the client may use it, and
+/// the ID token only with the `openid` scope.
+async fn issue(
+    app_state: &AppState,
+    transaction: &mut Transaction<'_, Postgres>,
+    client: &OauthClient,
+    customer: &Customer,
+    session_id: Uuid,
+    scope: String,
+    nonce: Option<String>
+) -> Result<TokenDtoResponse, AppError> {
+    let expires_in = Environment::access_token_ttl_seconds();
+    let claims = AuthorizationClaims::new(customer.id, session_id, customer.email.clone(), Vec::new(), expires_in)
+        .for_client(client.id, scope.clone());
+    let access_token = AuthorizationClaims::generate_jwt(&claims, &app_state.jwt_key_ring)?;
+    let refresh_token = if client.allows_grant(OauthGrantType::RefreshToken) {
+        let (refresh_token, plain_refresh_token) = RefreshToken::new(customer.id, session_id);
+        RefreshToken::insert(transaction, refresh_token).await?;
+        Some(plain_refresh_token)
+    } else {
+        None
+    };
+    let id_token = if claims.has_scope("openid") {
+        let mut id_token = IdTokenClaims::new(Environment::oauth_issuer(), customer.id, client.id, nonce, expires_in);
+        if claims.has_scope("email") {
+            id_token.email = Some(customer.email.clone());
+            id_token.email_verified = Some(customer.email_verified_at.is_some());
+        }
+        if claims.has_scope("profile") {
+            id_token.name = customer.name.clone();
+        }
+        Some(IdTokenClaims::generate_jwt(&id_token, &app_state.jwt_key_ring)?)
+    } else {
+        None
+    };
+    Ok(TokenDtoResponse {
+        access_token,
+        token_type: String::from(BEARER_TOKEN_TYPE),
+        expires_in,
+        scope,
+        refresh_token,
+        id_token,
+    })
+}
+
+fn invalid_grant(message: &str) -> AppError {
+    oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", message)
+}
@@ -0,0 +1,30 @@
+use axum::extract::State;
+use hyper::StatusCode;
+
+use crate::{feature::auth::{authorization::ScopedCustomer, domain::Customer}, infra::{axum::AppJsonResponse, errors::AppError}, state::AppState};
+
+use super::domain::{oauth_error, UserInfoDtoResponse};
+
+pub struct UserInfoUseCase;
+impl UserInfoUseCase {
+    /// Claims are released according to the scope of the access token, tokens of `/auth/singin`
+    /// have no scope and get all of them.
+    pub async fn userinfo(
+        State(app_state): State<AppState>,
+        ScopedCustomer(claims): ScopedCustomer,
+    ) -> Result<AppJsonResponse<UserInfoDtoResponse>, AppError> {
+        if !claims.has_scope("openid") {
+            return Err(oauth_error(StatusCode::FORBIDDEN, "insufficient_scope", "The openid scope is expected"));
+        }
+        let mut transaction = app_state.begin_transaction().await?;
+        let customer = Customer::get_by_id(&mut transaction, claims.sub).await?;
+        app_state.commit_transaction(transaction).await?;
+        let with_email = claims.has_scope("email");
+        Ok(AppJsonResponse::new(UserInfoDtoResponse {
+            sub: customer.id,
+            email_verified: with_email.then_some(customer.email_verified_at.is_some()),
+            email: with_email.then_some(customer.email),
+            name: customer.name.filter(|_| claims.has_scope("profile")),
+        }))
+    }
+}
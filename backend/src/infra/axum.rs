@@ -1,6 +1,12 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use crate::infra::env::Environment;
 use crate::infra::errors::AppError;
 use crate::infra::observability::Tags;
-use axum::extract::FromRequest;
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequest, FromRequestParts};
+use axum::http::request::Parts;
 use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use bytes::{BufMut, BytesMut};
@@ -10,6 +16,76 @@ use serde::Serialize;
 #[from_request(via(axum::Json), rejection(AppError))]
 pub struct AppJsonRequest<T>(pub T);
 
+/// `application/x-www-form-urlencoded` bodies, which is what OAuth clients send.
+#[derive(FromRequest)]
+#[from_request(via(axum::Form), rejection(AppError))]
+pub struct AppFormRequest<T>(pub T);
+
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+const DEVICE_NAME_HEADER: &str = "X-Device-Name";
+const UNKNOWN_CLIENT_IP: &str = "unknown";
+const MAX_DEVICE_NAME_LENGTH: usize = 128;
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Address of the caller. `X-Forwarded-For` is only honoured with `TRUST_FORWARDED_FOR=true`,
+/// meaning the app runs behind a proxy that sets it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientIp(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let forwarded_for = parts
+            .headers
+            .get(FORWARDED_FOR_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty() && Environment::trust_forwarded_for());
+        let client_ip = forwarded_for.unwrap_or_else(|| {
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip().to_string())
+                .unwrap_or_else(|| String::from(UNKNOWN_CLIENT_IP))
+        });
+        Ok(ClientIp(client_ip))
+    }
+}
+
+/// What a session is shown with when a customer lists where they are signed in. The device
+/// name comes from the optional `X-Device-Name` header set by the app.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientDevice {
+    pub ip: String,
+    pub user_agent: Option<String>,
+    pub device_name: Option<String>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientDevice {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let header_value = |name: &str, max_length: usize| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().chars().take(max_length).collect::<String>())
+                .filter(|value| !value.is_empty())
+        };
+        Ok(ClientDevice {
+            ip,
+            user_agent: header_value(header::USER_AGENT.as_str(), MAX_USER_AGENT_LENGTH),
+            device_name: header_value(DEVICE_NAME_HEADER, MAX_DEVICE_NAME_LENGTH),
+        })
+    }
+}
+
 pub struct AppJsonResponse<T> {
     body: T,
     tags: Tags,
@@ -1,14 +1,16 @@
+use std::collections::BTreeMap;
 use std::string::FromUtf8Error;
 
 use crate::infra::observability::Tags;
-use axum::extract::rejection::{JsonDataError, JsonRejection};
+use axum::extract::rejection::{FormRejection, JsonDataError, JsonRejection};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use bcrypt::BcryptError;
 use data_encoding::DecodeError;
 use hex::FromHexError;
-use hyper::header::InvalidHeaderValue;
+use hyper::header::{HeaderName, HeaderValue, InvalidHeaderValue};
+use hyper::HeaderMap;
 use rsa::pkcs8::spki;
 use serde_json::json;
 use tracing::{error, info};
@@ -21,17 +23,25 @@ pub enum AppError {
     Business(Box<AppErrorData>),
     AxumJsonRejection(JsonRejection),
     AxumJsonDataError(JsonDataError),
+    AxumFormRejection(FormRejection),
 }
 
 pub trait ToBusinessError {
     fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError;
 }
 
+/// Validation messages keyed by the request field they refer to.
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
 #[derive(Debug)]
 pub struct AppErrorData {
     pub status: StatusCode,
     pub message: String,
     tags: Option<Tags>,
+    pub errors: Option<FieldErrors>,
+    /// Error code of RFC 6749, OAuth endpoints answer with it instead of the usual body.
+    pub oauth_error: Option<&'static str>,
+    pub headers: HeaderMap,
 }
 
 impl AppErrorData {
@@ -40,9 +50,27 @@ impl AppErrorData {
             status,
             message,
             tags,
+            errors: None,
+            oauth_error: None,
+            headers: HeaderMap::new(),
         }
     }
 
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn with_errors(mut self, errors: FieldErrors) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+
+    pub fn with_oauth_error(mut self, code: &'static str) -> Self {
+        self.oauth_error = Some(code);
+        self
+    }
+
     pub fn to_business_error(self) -> AppError {
         AppError::Business(Box::new(self))
     }
@@ -61,10 +89,20 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let app_error_data = to_app_error_data(self);
 
-        let body = Json(json!({
+        let mut body = json!({
             "status": app_error_data.status.as_u16(),
             "message": app_error_data.message,
-        }));
+        });
+        if let Some(errors) = &app_error_data.errors {
+            body["errors"] = json!(errors);
+        }
+        if let Some(code) = app_error_data.oauth_error {
+            body = json!({
+                "error": code,
+                "error_description": app_error_data.message,
+            });
+        }
+        let body = Json(body);
 
         let tags = app_error_data.get_tags();
         let status_code = app_error_data.status;
@@ -72,12 +110,14 @@ impl IntoResponse for AppError {
         if status_code.is_server_error() {
             error!(
                 user_id = tags.user_id,
+                api_key_id = tags.api_key_id,
                 "{}",
                 app_error_data.message.clone()
             );
         } else {
             info!(
                 user_id = tags.user_id,
+                api_key_id = tags.api_key_id,
                 "{}",
                 app_error_data.message.clone()
             );
@@ -87,6 +127,7 @@ impl IntoResponse for AppError {
 
         let (mut parts, body) = response.into_parts();
         parts.extensions.insert(app_error_data.get_tags());
+        parts.headers.extend(app_error_data.headers);
 
         Response::from_parts(parts, body)
     }
@@ -115,6 +156,11 @@ fn to_app_error_data(app_error: AppError) -> Box<AppErrorData> {
             rejection.body_text(),
             None,
         )),
+        AppError::AxumFormRejection(rejection) => Box::new(AppErrorData::new(
+            rejection.status(),
+            rejection.body_text(),
+            None,
+        )),
     }
 }
 
@@ -142,6 +188,12 @@ impl From<JsonDataError> for AppError {
     }
 }
 
+impl From<FormRejection> for AppError {
+    fn from(inner: FormRejection) -> Self {
+        AppError::AxumFormRejection(inner)
+    }
+}
+
 impl ToBusinessError for std::fmt::Error {
     fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
         let message = format!("General Error: {} :: {}", message, self);
@@ -239,3 +291,45 @@ impl ToBusinessError for jsonwebtoken::errors::Error {
         AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
     }
 }
+
+impl ToBusinessError for std::io::Error {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("IO Error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
+
+impl ToBusinessError for lettre::address::AddressError {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("Mail address Error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
+
+impl ToBusinessError for lettre::error::Error {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("Mail Error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
+
+impl ToBusinessError for lettre::transport::smtp::Error {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("SMTP Error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
+
+impl ToBusinessError for argon2::Error {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("Argon2 error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
+
+impl ToBusinessError for argon2::password_hash::Error {
+    fn to_business_error(&self, message: &str, tags: Option<Tags>) -> AppError {
+        let message = format!("Password hash error: {} :: {}", message, self);
+        AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, tags).to_business_error()
+    }
+}
@@ -1,6 +1,8 @@
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Tags {
     pub user_id: Option<String>,
+    /// Set when the caller authenticated with an API key instead of a customer token.
+    pub api_key_id: Option<String>,
 }
 
 pub trait ToTags {
@@ -11,6 +13,7 @@ impl ToTags for &Tags {
     fn to_tags(&self) -> Tags {
         Tags {
             user_id: self.user_id.clone(),
+            api_key_id: self.api_key_id.clone(),
         }
     }
 }
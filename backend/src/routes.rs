@@ -1,18 +1,89 @@
-use axum::{routing::{post, put, get}, Router};
+use axum::{middleware::from_fn_with_state, routing::{post, put, get, delete}, Router};
 
-use crate::{app::AppRoutes, feature::{auth::{sing_in::SingInUseCase, sing_up::SingUpUseCase}, biometrics::{create::CreateUseCase, get_by::GetByUseCase, update::UpdateUseCase}}, state::AppState};
+use crate::{app::AppRoutes, feature::{admin::{api_keys::ApiKeysUseCase, oauth_clients::OauthClientsUseCase, roles::RolesUseCase, unlock::UnlockUseCase}, auth::{authorization::RequireRole, domain::Role, jwks::JwksUseCase, logout::LogoutUseCase, magic_link::MagicLinkUseCase, mfa::MfaUseCase, passkey::PasskeyUseCase, password::PasswordUseCase, refresh::RefreshUseCase, sessions::SessionsUseCase, sing_in::SingInUseCase, sing_up::SingUpUseCase, sms::SmsUseCase, verify_email::VerifyEmailUseCase}, biometrics::{create::CreateUseCase, get_by::GetByUseCase, image::ImageUseCase, update::UpdateUseCase}, customers::{export::ExportUseCase, me::MeUseCase}, oauth::{authorize::AuthorizeUseCase, discovery::DiscoveryUseCase, introspect::IntrospectUseCase, revoke::RevokeUseCase, token::TokenUseCase, userinfo::UserInfoUseCase}}, state::AppState};
 
 impl AppRoutes {
     pub fn auth_routes() -> Router<AppState> {
         Router::new()
             .route("/singin", post(SingInUseCase::sing_in))
             .route("/singup", post(SingUpUseCase::sing_up))
+            .route("/refresh", post(RefreshUseCase::refresh))
+            .route("/logout", post(LogoutUseCase::logout))
+            .route("/sessions", get(SessionsUseCase::list))
+            .route("/sessions/:session_id", delete(SessionsUseCase::revoke))
+            .route("/verify-email", post(VerifyEmailUseCase::verify_email))
+            .route("/verify-email/resend", post(VerifyEmailUseCase::resend))
+            .route("/password/forgot", post(PasswordUseCase::forgot))
+            .route("/password/reset", post(PasswordUseCase::reset))
+            .route("/magic-link", post(MagicLinkUseCase::request))
+            .route("/magic-link/consume", post(MagicLinkUseCase::consume))
+            .route("/phone", put(SmsUseCase::update_phone_number))
+            .route("/phone/confirm", post(SmsUseCase::confirm_phone_number))
+            .route("/sms/request", post(SmsUseCase::request))
+            .route("/sms/verify", post(SmsUseCase::verify))
+            .route("/mfa/enroll", post(MfaUseCase::enroll))
+            .route("/mfa/confirm", post(MfaUseCase::confirm))
+            .route("/mfa/disable", post(MfaUseCase::disable))
+            .route("/mfa/recovery-codes", post(MfaUseCase::regenerate_recovery_codes))
+            .route("/mfa/verify", post(MfaUseCase::verify))
+            .route("/passkey/register/options", post(PasskeyUseCase::registration_options))
+            .route("/passkey/register", post(PasskeyUseCase::register))
+            .route("/passkey/authenticate/options", post(PasskeyUseCase::authentication_options))
+            .route("/passkey/authenticate", post(PasskeyUseCase::authenticate))
     }
 
-    pub fn biometrics_routes() -> Router<AppState> {
+    /// The image download authorizes itself through its signed link, so it is left out of the role check.
+    pub fn biometrics_routes(app_state: &AppState) -> Router<AppState> {
         Router::new()
             .route("/actions/create", post(CreateUseCase::create))
             .route("/actions/get/:customer_id", get(GetByUseCase::get_by))
             .route("/actions/update", put(UpdateUseCase::update))
+            .route_layer(from_fn_with_state(
+                RequireRole::any_of(app_state, &[Role::Customer, Role::Reviewer]).or_api_key(),
+                RequireRole::check,
+            ))
+            .route("/actions/image", get(ImageUseCase::download))
+    }
+
+    pub fn customers_routes(app_state: &AppState) -> Router<AppState> {
+        Router::new()
+            .route("/me", get(MeUseCase::get).patch(MeUseCase::update).delete(MeUseCase::delete))
+            .route("/me/password", post(MeUseCase::change_password))
+            .route("/me/email", post(MeUseCase::change_email))
+            .route("/me/deletion", delete(MeUseCase::cancel_deletion))
+            .route("/me/export", get(ExportUseCase::export))
+            .route_layer(from_fn_with_state(
+                RequireRole::any_of(app_state, &[Role::Customer]),
+                RequireRole::check,
+            ))
+    }
+
+    pub fn admin_routes(app_state: &AppState) -> Router<AppState> {
+        Router::new()
+            .route("/customers/:customer_id/unlock", post(UnlockUseCase::unlock))
+            .route("/customers/:customer_id/roles", put(RolesUseCase::update))
+            .route("/api-keys", post(ApiKeysUseCase::create).get(ApiKeysUseCase::list))
+            .route("/api-keys/:api_key_id", delete(ApiKeysUseCase::revoke))
+            .route("/oauth-clients", post(OauthClientsUseCase::create).get(OauthClientsUseCase::list))
+            .route("/oauth-clients/:client_id", delete(OauthClientsUseCase::revoke))
+            .route_layer(from_fn_with_state(
+                RequireRole::any_of(app_state, &[Role::Admin]),
+                RequireRole::check,
+            ))
+    }
+
+    pub fn oauth_routes() -> Router<AppState> {
+        Router::new()
+            .route("/authorize", get(AuthorizeUseCase::authorize).post(AuthorizeUseCase::approve))
+            .route("/token", post(TokenUseCase::token))
+            .route("/userinfo", get(UserInfoUseCase::userinfo).post(UserInfoUseCase::userinfo))
+            .route("/introspect", post(IntrospectUseCase::introspect))
+            .route("/revoke", post(RevokeUseCase::revoke))
+    }
+
+    pub fn well_known_routes() -> Router<AppState> {
+        Router::new()
+            .route("/jwks.json", get(JwksUseCase::jwks))
+            .route("/openid-configuration", get(DiscoveryUseCase::openid_configuration))
     }
 }
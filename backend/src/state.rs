@@ -1,17 +1,35 @@
+use std::sync::Arc;
+
 use sqlx::{Pool, Postgres};
 
-use crate::infra::database::DatabaseConfig;
+use crate::{infra::database::DatabaseConfig, support::{hash::hashing_pool::HashingPool, jwt_keys::JwtKeyRing, mail::{MailSender, MailSenders}, sms::{SmsSender, SmsSenders}, storage::{ImageStorage, ImageStorages}}};
 #[derive(Clone)]
 pub struct AppState {
     pub postgres_pool: Pool<Postgres>,
+    pub jwt_key_ring: JwtKeyRing,
+    pub mail_sender: Arc<dyn MailSender>,
+    pub sms_sender: Arc<dyn SmsSender>,
+    pub image_storage: Arc<dyn ImageStorage>,
+    pub hashing_pool: HashingPool,
 }
 
 impl AppState {
     pub async fn create() -> Result<AppState, Box<dyn std::error::Error>> {
         let database_config = DatabaseConfig::from_env();
         let postgres_pool = database_config.create_db_pool().await?;
+        let jwt_key_ring = JwtKeyRing::from_env()
+            .map_err(|err| format!("Failed to load JWT key ring: {:?}", err))?;
+        let mail_sender = MailSenders::from_env()
+            .map_err(|err| format!("Failed to create mail sender: {:?}", err))?;
+        let sms_sender = SmsSenders::from_env()
+            .map_err(|err| format!("Failed to create sms sender: {:?}", err))?;
         let app_state = AppState {
             postgres_pool,
+            jwt_key_ring,
+            mail_sender,
+            sms_sender,
+            image_storage: ImageStorages::from_env(),
+            hashing_pool: HashingPool::from_env(),
         };
         Ok(app_state)
     }
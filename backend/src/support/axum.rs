@@ -7,6 +7,8 @@ use http_body_util::BodyExt;
 use tracing::log::{log_enabled, Level};
 use tracing::{error, info};
 
+use crate::infra::observability::Tags;
+
 pub async fn log_response(
     req: Request<Body>,
     next: Next,
@@ -18,13 +20,14 @@ pub async fn log_response(
 
     let status = res.status();
     let (parts, body) = res.into_parts();
+    let tags = parts.extensions.get::<Tags>().cloned().unwrap_or_default();
 
-    let bytes = buffer_and_print(method, uri, status, body).await;
+    let bytes = buffer_and_print(method, uri, status, tags, body).await;
 
     Ok(Response::from_parts(parts, Body::from(bytes)))
 }
 
-async fn buffer_and_print(method: Method, uri: Uri, status: StatusCode, body: Body) -> Bytes {
+async fn buffer_and_print(method: Method, uri: Uri, status: StatusCode, tags: Tags, body: Body) -> Bytes {
     let body_bytes = body
         .collect()
         .await
@@ -41,11 +44,15 @@ async fn buffer_and_print(method: Method, uri: Uri, status: StatusCode, body: Bo
 
     if status.is_server_error() {
         error!(
+            user_id = tags.user_id,
+            api_key_id = tags.api_key_id,
             "{method} {uri} -> {} :: response :: {body_string}",
             status.as_u16(),
         );
     } else {
         info!(
+            user_id = tags.user_id,
+            api_key_id = tags.api_key_id,
             "{method} {uri} -> {} :: response :: {body_string}",
             status.as_u16(),
         );
@@ -0,0 +1,89 @@
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use data_encoding::BASE64;
+use hyper::StatusCode;
+use rand::{rngs::OsRng, RngCore};
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData, ToBusinessError}};
+
+const NONCE_BYTES: usize = 12;
+
+/// Reversible encryption for secrets that have to be read back, like TOTP secrets.
+/// The random nonce is stored in front of the ciphertext.
+pub struct HashAesGcm {
+    cipher: Aes256Gcm,
+}
+
+impl HashAesGcm {
+    pub fn new(base64_key: &str) -> Result<Self, AppError> {
+        let key = BASE64
+            .decode(base64_key.as_bytes())
+            .map_err(|err| err.to_business_error("Failed to decode AES key", None))?;
+        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| {
+            AppErrorData::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("AES Error: key must have 32 bytes"),
+                None,
+            )
+            .to_business_error()
+        })?;
+        Ok(Self { cipher })
+    }
+
+    pub fn from_env() -> Result<Self, AppError> {
+        Self::new(&Environment::mfa_encryption_key())
+    }
+
+    pub fn encrypt(&self, plain: &[u8]) -> Result<String, AppError> {
+        let mut nonce = [0u8; NONCE_BYTES];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plain)
+            .map_err(|err| err.to_business_error("Failed to encrypt", None))?;
+        Ok(BASE64.encode(&[nonce.as_slice(), ciphertext.as_slice()].concat()))
+    }
+
+    pub fn decrypt(&self, encrypted: &str) -> Result<Vec<u8>, AppError> {
+        let bytes = BASE64
+            .decode(encrypted.as_bytes())
+            .map_err(|err| err.to_business_error("Failed to decode encrypted data", None))?;
+        if bytes.len() < NONCE_BYTES {
+            return Err(AppErrorData::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("AES Error: encrypted data is too short"),
+                None,
+            )
+            .to_business_error());
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_BYTES);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|err| err.to_business_error("Failed to decrypt", None))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{infra::errors::AppError, support::hash::hash_aes_gcm::HashAesGcm};
+
+    #[test]
+    fn should_encrypt_and_decrypt_successfully() -> Result<(), AppError> {
+        let aes = HashAesGcm::from_env()?;
+
+        let encrypted = aes.encrypt(b"plain_text")?;
+
+        assert_ne!(encrypted, aes.encrypt(b"plain_text")?);
+        assert_eq!(aes.decrypt(&encrypted)?, b"plain_text");
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_error_when_decrypt_with_other_key() -> Result<(), AppError> {
+        let encrypted = HashAesGcm::from_env()?.encrypt(b"plain_text")?;
+        let other = HashAesGcm::new("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=")?;
+
+        assert!(other.decrypt(&encrypted).is_err());
+        assert!(HashAesGcm::new("c2hvcnQ=").is_err());
+        Ok(())
+    }
+}
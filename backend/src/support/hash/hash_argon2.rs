@@ -0,0 +1,69 @@
+use argon2::{password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString}, Algorithm, Argon2, Params, Version};
+
+use crate::infra::{env::Environment, errors::{AppError, ToBusinessError}};
+
+use super::hash_password::PasswordHasher;
+
+const ARGON2ID_PREFIX: &str = "$argon2id$";
+
+pub struct HashArgon2 {
+    params: Params,
+}
+
+impl HashArgon2 {
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self, AppError> {
+        let params = Params::new(memory_kib, iterations, parallelism, None)
+            .map_err(|err| err.to_business_error("Invalid Argon2 params", None))?;
+        Ok(Self { params })
+    }
+
+    pub fn from_env() -> Result<Self, AppError> {
+        Self::new(
+            Environment::argon2_memory_kib(),
+            Environment::argon2_iterations(),
+            Environment::argon2_parallelism(),
+        )
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for HashArgon2 {
+    fn recognizes(&self, password_hashed: &str) -> bool {
+        password_hashed.starts_with(ARGON2ID_PREFIX)
+    }
+
+    fn encode(&self, password: &str) -> Result<String, AppError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hashed = self
+            .argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|err| err.to_business_error("Error to hash", None))?;
+        Ok(password_hashed.to_string())
+    }
+
+    /// The costs stored in the hash are used, not the configured ones.
+    fn verify(&self, password_hashed: &str, password: &str) -> Result<bool, AppError> {
+        let password_hash = PasswordHash::new(password_hashed)
+            .map_err(|err| err.to_business_error("Error to verify", None))?;
+        match self.argon2().verify_password(password.as_bytes(), &password_hash) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(err) => Err(err.to_business_error("Error to verify", None)),
+        }
+    }
+
+    fn is_outdated(&self, password_hashed: &str) -> bool {
+        PasswordHash::new(password_hashed)
+            .ok()
+            .and_then(|password_hash| Params::try_from(&password_hash).ok())
+            .map(|params| {
+                params.m_cost() != self.params.m_cost()
+                    || params.t_cost() != self.params.t_cost()
+                    || params.p_cost() != self.params.p_cost()
+            })
+            .unwrap_or(true)
+    }
+}
@@ -2,19 +2,32 @@ use bcrypt::{hash, verify, DEFAULT_COST};
 
 use crate::infra::errors::{AppError, ToBusinessError};
 
+use super::hash_password::PasswordHasher;
+
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+
+/// Only kept to verify hashes stored before the move to Argon2id.
 pub struct HashBCrypt;
-impl HashBCrypt {
-    pub fn encode(data: String) -> Result<String, AppError>{
+impl PasswordHasher for HashBCrypt {
+    fn recognizes(&self, password_hashed: &str) -> bool {
+        BCRYPT_PREFIXES.iter().any(|prefix| password_hashed.starts_with(prefix))
+    }
+
+    fn encode(&self, password: &str) -> Result<String, AppError> {
         Ok(
-            hash(&data, DEFAULT_COST)
+            hash(password, DEFAULT_COST)
                 .map_err(|err| err.to_business_error("Error to hash", None))?
         )
     }
 
-    pub fn verify(data_hashed: String, data: String) -> Result<bool, AppError>{
+    fn verify(&self, password_hashed: &str, password: &str) -> Result<bool, AppError> {
         Ok(
-            verify(&data, &data_hashed)
+            verify(password, password_hashed)
                 .map_err(|err| err.to_business_error("Error to verify", None))?
         )
     }
+
+    fn is_outdated(&self, _password_hashed: &str) -> bool {
+        true
+    }
 }
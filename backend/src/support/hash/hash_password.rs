@@ -0,0 +1,105 @@
+use std::sync::OnceLock;
+
+use hyper::StatusCode;
+
+use crate::infra::errors::{AppError, AppErrorData};
+
+use super::{hash_argon2::HashArgon2, hash_bcrypt::HashBCrypt};
+
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+pub trait PasswordHasher {
+    /// Whether the stored hash was produced by this algorithm.
+    fn recognizes(&self, password_hashed: &str) -> bool;
+    fn encode(&self, password: &str) -> Result<String, AppError>;
+    fn verify(&self, password_hashed: &str, password: &str) -> Result<bool, AppError>;
+    /// Whether a recognized hash was produced with other parameters than the configured ones.
+    fn is_outdated(&self, password_hashed: &str) -> bool;
+}
+
+/// New passwords are hashed with Argon2id. Stored hashes are verified with the algorithm
+/// named by their PHC prefix, so bcrypt hashes keep working until they are rehashed.
+pub struct HashPassword;
+impl HashPassword {
+    pub fn encode(password: &str) -> Result<String, AppError> {
+        HashArgon2::from_env()?.encode(password)
+    }
+
+    pub fn verify(password_hashed: &str, password: &str) -> Result<bool, AppError> {
+        let argon2 = HashArgon2::from_env()?;
+        if argon2.recognizes(password_hashed) {
+            return argon2.verify(password_hashed, password);
+        }
+        let bcrypt = HashBCrypt;
+        if bcrypt.recognizes(password_hashed) {
+            return bcrypt.verify(password_hashed, password);
+        }
+        Err(AppErrorData::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Unknown password hash format"),
+            None,
+        )
+        .to_business_error())
+    }
+
+    /// True for anything other than Argon2id with the configured costs.
+    pub fn needs_rehash(password_hashed: &str) -> Result<bool, AppError> {
+        let argon2 = HashArgon2::from_env()?;
+        Ok(!argon2.recognizes(password_hashed) || argon2.is_outdated(password_hashed))
+    }
+
+    /// Spends the same time as `verify` for callers that have no hash to check against,
+    /// so the response time does not tell whether there was one. The dummy uses the configured
+    /// algorithm and costs, which every hash is moved to on sign in. Always returns false.
+    pub fn verify_dummy(password: &str) -> Result<bool, AppError> {
+        let dummy_hash = match DUMMY_HASH.get() {
+            Some(dummy_hash) => dummy_hash,
+            None => {
+                let dummy_hash = Self::encode("dummy password")?;
+                DUMMY_HASH.get_or_init(|| dummy_hash)
+            }
+        };
+        Self::verify(dummy_hash, password)?;
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_verify_password_hashed_with_argon2id() -> Result<(), AppError> {
+        let password_hashed = HashPassword::encode("my$ecr3T")?;
+
+        assert!(password_hashed.starts_with("$argon2id$"));
+        assert!(HashPassword::verify(&password_hashed, "my$ecr3T")?);
+        assert!(!HashPassword::verify(&password_hashed, "wr0ng$ecreT")?);
+        assert!(!HashPassword::needs_rehash(&password_hashed)?);
+        Ok(())
+    }
+
+    #[test]
+    fn should_verify_and_ask_rehash_of_bcrypt_password() -> Result<(), AppError> {
+        let password_hashed = HashBCrypt.encode("my$ecr3T")?;
+
+        assert!(HashPassword::verify(&password_hashed, "my$ecr3T")?);
+        assert!(!HashPassword::verify(&password_hashed, "wr0ng$ecreT")?);
+        assert!(HashPassword::needs_rehash(&password_hashed)?);
+        Ok(())
+    }
+
+    #[test]
+    fn should_ask_rehash_when_argon2_costs_changed() -> Result<(), AppError> {
+        let password_hashed = HashArgon2::new(8, 1, 1)?.encode("my$ecr3T")?;
+
+        assert!(HashPassword::verify(&password_hashed, "my$ecr3T")?);
+        assert!(HashPassword::needs_rehash(&password_hashed)?);
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_error_when_hash_format_is_unknown() {
+        assert!(HashPassword::verify("plain", "plain").is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use sha2::{Digest, Sha256};
+
+pub struct HashSha256;
+impl HashSha256 {
+    pub fn encode(data: &str) -> String {
+        hex::encode(Sha256::digest(data.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::support::hash::hash_sha256::HashSha256;
+
+    #[test]
+    fn should_encode_same_hash_for_same_data() {
+        let hashed = HashSha256::encode("plain_text");
+
+        assert_eq!(hashed.len(), 64);
+        assert_eq!(hashed, HashSha256::encode("plain_text"));
+        assert_ne!(hashed, HashSha256::encode("other_text"));
+    }
+}
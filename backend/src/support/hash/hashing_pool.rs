@@ -0,0 +1,101 @@
+use std::{sync::Arc, time::Duration};
+
+use hyper::{header::{HeaderValue, RETRY_AFTER}, StatusCode};
+use tokio::sync::Semaphore;
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData}};
+
+use super::hash_password::HashPassword;
+
+/// Runs password hashing on Tokio's blocking threads, at most `max_concurrency` at a time,
+/// so it never stalls the async workers. Callers waiting longer than `queue_timeout`
+/// for a slot are turned away with a 503 instead of piling up.
+#[derive(Clone)]
+pub struct HashingPool {
+    semaphore: Arc<Semaphore>,
+    queue_timeout: Duration,
+}
+
+impl HashingPool {
+    pub fn new(max_concurrency: usize, queue_timeout: Duration) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrency.max(1))),
+            queue_timeout,
+        }
+    }
+
+    /// Defaults to one slot per available CPU.
+    pub fn from_env() -> Self {
+        let available_parallelism = std::thread::available_parallelism().map(|value| value.get()).unwrap_or(1);
+        let max_concurrency = match Environment::hashing_max_concurrency() {
+            0 => available_parallelism,
+            max_concurrency => max_concurrency as usize,
+        };
+        Self::new(max_concurrency, Duration::from_millis(Environment::hashing_queue_timeout_ms()))
+    }
+
+    pub async fn encode(&self, password: String) -> Result<String, AppError> {
+        self.run(move || HashPassword::encode(&password)).await
+    }
+
+    pub async fn verify(&self, password_hashed: String, password: String) -> Result<bool, AppError> {
+        self.run(move || HashPassword::verify(&password_hashed, &password)).await
+    }
+
+    pub async fn verify_dummy(&self, password: String) -> Result<bool, AppError> {
+        self.run(move || HashPassword::verify_dummy(&password)).await
+    }
+
+    async fn run<T, F>(&self, hashing: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, AppError> + Send + 'static,
+    {
+        let permit = tokio::time::timeout(self.queue_timeout, self.semaphore.clone().acquire_owned())
+            .await
+            .ok()
+            .and_then(|permit| permit.ok())
+            .ok_or_else(|| {
+                AppErrorData::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    String::from("Too many requests being processed, try again later"),
+                    None,
+                )
+                .with_header(RETRY_AFTER, HeaderValue::from(1))
+                .to_business_error()
+            })?;
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            hashing()
+        })
+        .await
+        .map_err(|err| {
+            let message = format!("Hashing task failed: {}", err);
+            AppErrorData::new(StatusCode::INTERNAL_SERVER_ERROR, message, None).to_business_error()
+        })?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_return_service_unavailable_when_pool_is_saturated() -> Result<(), AppError> {
+        let pool = HashingPool::new(1, Duration::ZERO);
+        let busy_pool = pool.clone();
+        let busy = tokio::spawn(async move {
+            busy_pool.run(|| {
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(())
+            }).await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let shed = pool.run(|| Ok(())).await;
+
+        assert!(matches!(shed, Err(AppError::Business(data)) if data.status == StatusCode::SERVICE_UNAVAILABLE));
+        busy.await.expect("Failed to join busy task")?;
+        pool.run(|| Ok(())).await
+    }
+}
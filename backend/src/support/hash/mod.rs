@@ -1,2 +1,7 @@
 pub mod base64;
+pub mod hash_aes_gcm;
+pub mod hash_argon2;
 pub mod hash_bcrypt;
+pub mod hash_password;
+pub mod hash_sha256;
+pub mod hashing_pool;
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::infra::{env::Environment, errors::{AppError, ToBusinessError}};
+
+use super::{MailMessage, MailSender};
+
+/// Writes each message as a text file under `MAIL_FILE_DIR` instead of delivering it.
+pub struct FileMailSender {
+    dir: PathBuf,
+}
+
+impl FileMailSender {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(PathBuf::from(Environment::mail_file_dir()))
+    }
+}
+
+#[async_trait]
+impl MailSender for FileMailSender {
+    async fn send(&self, message: MailMessage) -> Result<(), AppError> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|err| err.to_business_error("create mail dir", None))?;
+        let file_name = format!("{}_{}.txt", Utc::now().format("%Y%m%dT%H%M%S"), Uuid::now_v7());
+        let content = format!("To: {}\nSubject: {}\n\n{}\n", message.to, message.subject, message.body);
+        tokio::fs::write(self.dir.join(file_name), content)
+            .await
+            .map_err(|err| err.to_business_error("write mail file", None))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_write_message_to_file() -> Result<(), AppError> {
+        let dir = std::env::temp_dir().join(format!("mails_{}", Uuid::now_v7()));
+        let sender = FileMailSender::new(dir.clone());
+
+        sender.send(MailMessage {
+            to: String::from("user@fiap.com.br"),
+            subject: String::from("Hello"),
+            body: String::from("World"),
+        }).await?;
+
+        let mut entries = std::fs::read_dir(&dir).expect("Failed to read mail dir");
+        let path = entries.next().expect("Expected a mail file").expect("Failed to read entry").path();
+        let content = std::fs::read_to_string(path).expect("Failed to read mail file");
+        assert_eq!(content, "To: user@fiap.com.br\nSubject: Hello\n\nWorld\n");
+        std::fs::remove_dir_all(dir).expect("Failed to remove mail dir");
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::{Arc, Mutex};
+
+use axum::async_trait;
+
+use crate::infra::errors::AppError;
+
+use super::{MailMessage, MailSender};
+
+/// Keeps every message in memory, clones share the same outbox.
+#[derive(Clone, Default)]
+pub struct InMemoryMailSender {
+    messages: Arc<Mutex<Vec<MailMessage>>>,
+}
+
+impl InMemoryMailSender {
+    pub fn messages(&self) -> Vec<MailMessage> {
+        self.messages.lock().expect("Failed to lock mail outbox").clone()
+    }
+
+    pub fn last_message_to(&self, to: &str) -> Option<MailMessage> {
+        self.messages().into_iter().rev().find(|message| message.to == to)
+    }
+}
+
+#[async_trait]
+impl MailSender for InMemoryMailSender {
+    async fn send(&self, message: MailMessage) -> Result<(), AppError> {
+        self.messages.lock().expect("Failed to lock mail outbox").push(message);
+        Ok(())
+    }
+}
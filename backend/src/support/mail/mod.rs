@@ -0,0 +1,36 @@
+pub mod file;
+pub mod memory;
+pub mod smtp;
+
+use std::sync::Arc;
+
+use axum::async_trait;
+
+use crate::infra::{env::Environment, errors::AppError};
+
+use self::{file::FileMailSender, memory::InMemoryMailSender, smtp::SmtpMailSender};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, message: MailMessage) -> Result<(), AppError>;
+}
+
+pub struct MailSenders;
+impl MailSenders {
+    /// `MAIL_SENDER` picks the implementation: `smtp`, `file` (the default, for local runs) or `memory`.
+    pub fn from_env() -> Result<Arc<dyn MailSender>, AppError> {
+        let mail_sender: Arc<dyn MailSender> = match Environment::mail_sender().as_str() {
+            "smtp" => Arc::new(SmtpMailSender::from_env()?),
+            "memory" => Arc::new(InMemoryMailSender::default()),
+            _ => Arc::new(FileMailSender::from_env()),
+        };
+        Ok(mail_sender)
+    }
+}
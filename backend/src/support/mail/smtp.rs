@@ -0,0 +1,53 @@
+use axum::async_trait;
+use lettre::{message::{header::ContentType, Mailbox}, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::infra::{env::Environment, errors::{AppError, ToBusinessError}};
+
+use super::{MailMessage, MailSender};
+
+pub struct SmtpMailSender {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailSender {
+    /// Uses STARTTLS against `SMTP_HOST`, credentials are only sent when `SMTP_USER` is set.
+    pub fn from_env() -> Result<Self, AppError> {
+        let from = Environment::mail_from()
+            .parse::<Mailbox>()
+            .map_err(|err| err.to_business_error("Failed to parse MAIL_FROM", None))?;
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&Environment::smtp_host())
+            .map_err(|err| err.to_business_error("Failed to create SMTP transport", None))?
+            .port(Environment::smtp_port());
+        let user = Environment::smtp_user();
+        if !user.is_empty() {
+            builder = builder.credentials(Credentials::new(user, Environment::smtp_pass()));
+        }
+        Ok(Self {
+            from,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl MailSender for SmtpMailSender {
+    async fn send(&self, message: MailMessage) -> Result<(), AppError> {
+        let to = message
+            .to
+            .parse::<Mailbox>()
+            .map_err(|err| err.to_business_error("parse mail recipient", None))?;
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|err| err.to_business_error("build mail", None))?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|err| err.to_business_error("send mail", None))?;
+        Ok(())
+    }
+}
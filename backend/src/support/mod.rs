@@ -1,3 +1,10 @@
 pub mod axum;
 pub mod hash;
 pub mod jwt;
+pub mod jwt_keys;
+pub mod mail;
+pub mod random;
+pub mod sms;
+pub mod storage;
+pub mod totp;
+pub mod webauthn;
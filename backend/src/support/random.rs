@@ -0,0 +1,63 @@
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
+use rand::{rngs::OsRng, Rng, RngCore};
+
+const OPAQUE_TOKEN_BYTES: usize = 32;
+const CODE_BYTES: usize = 7;
+const CODE_GROUP_LENGTH: usize = 5;
+
+pub struct RandomToken;
+impl RandomToken {
+    pub fn generate() -> String {
+        let mut bytes = [0u8; OPAQUE_TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        BASE64URL_NOPAD.encode(&bytes)
+    }
+}
+
+/// Short codes meant to be written down and typed by people, like `k3m9q-x2p7d`.
+pub struct RandomCode;
+impl RandomCode {
+    pub fn generate() -> String {
+        let mut bytes = [0u8; CODE_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+        format!("{}-{}", &code[..CODE_GROUP_LENGTH], &code[CODE_GROUP_LENGTH..CODE_GROUP_LENGTH * 2])
+    }
+
+    /// Six uniformly distributed digits, leading zeros included.
+    pub fn generate_digits() -> String {
+        format!("{:06}", OsRng.gen_range(0..1_000_000))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::support::random::{RandomCode, RandomToken};
+
+    #[test]
+    fn should_generate_distinct_url_safe_tokens() {
+        let token = RandomToken::generate();
+
+        assert_eq!(token.len(), 43);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(token, RandomToken::generate());
+    }
+
+    #[test]
+    fn should_generate_distinct_grouped_codes() {
+        let code = RandomCode::generate();
+
+        assert_eq!(code.len(), 11);
+        assert_eq!(code.chars().nth(5), Some('-'));
+        assert!(code.chars().all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c) || c == '-'));
+        assert_ne!(code, RandomCode::generate());
+    }
+
+    #[test]
+    fn should_generate_six_digits_code() {
+        let code = RandomCode::generate_digits();
+
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+}
@@ -0,0 +1,65 @@
+use axum::async_trait;
+use hyper::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData, ToBusinessError}, http_client::new_client};
+
+use super::{SmsMessage, SmsSender};
+
+#[derive(Serialize)]
+struct SmsDtoRequest {
+    to: String,
+    message: String,
+}
+
+/// Posts each message as JSON to `SMS_API_URL`, authenticated with the `SMS_API_TOKEN` bearer token.
+pub struct HttpSmsSender {
+    client: ClientWithMiddleware,
+    url: String,
+    token: String,
+}
+
+impl HttpSmsSender {
+    pub fn new(url: String, token: String, timeout_seconds: u64) -> Result<Self, AppError> {
+        let client = new_client("sms", timeout_seconds).map_err(|err| {
+            AppErrorData::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create SMS client: {}", err),
+                None,
+            )
+            .to_business_error()
+        })?;
+        Ok(Self { client, url, token })
+    }
+
+    pub fn from_env() -> Result<Self, AppError> {
+        Self::new(
+            Environment::sms_api_url(),
+            Environment::sms_api_token(),
+            Environment::sms_api_timeout_seconds(),
+        )
+    }
+}
+
+#[async_trait]
+impl SmsSender for HttpSmsSender {
+    async fn send(&self, message: SmsMessage) -> Result<(), AppError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .json(&SmsDtoRequest {
+                to: message.to,
+                message: message.body,
+            })
+            .send()
+            .await
+            .map_err(|err| err.to_business_error("send sms", None))?;
+        if !response.status().is_success() {
+            let message = format!("SMS provider answered with status {}", response.status().as_u16());
+            return Err(AppErrorData::new(StatusCode::BAD_GATEWAY, message, None).to_business_error());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use axum::async_trait;
+use tracing::info;
+
+use crate::infra::errors::AppError;
+
+use super::{SmsMessage, SmsSender};
+
+/// Writes each message to the log instead of delivering it.
+pub struct LogSmsSender;
+
+#[async_trait]
+impl SmsSender for LogSmsSender {
+    async fn send(&self, message: SmsMessage) -> Result<(), AppError> {
+        info!("SMS to {}: {}", message.to, message.body);
+        Ok(())
+    }
+}
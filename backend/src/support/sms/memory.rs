@@ -0,0 +1,31 @@
+use std::sync::{Arc, Mutex};
+
+use axum::async_trait;
+
+use crate::infra::errors::AppError;
+
+use super::{SmsMessage, SmsSender};
+
+/// Keeps every message in memory, clones share the same outbox.
+#[derive(Clone, Default)]
+pub struct InMemorySmsSender {
+    messages: Arc<Mutex<Vec<SmsMessage>>>,
+}
+
+impl InMemorySmsSender {
+    pub fn messages(&self) -> Vec<SmsMessage> {
+        self.messages.lock().expect("Failed to lock sms outbox").clone()
+    }
+
+    pub fn last_message_to(&self, to: &str) -> Option<SmsMessage> {
+        self.messages().into_iter().rev().find(|message| message.to == to)
+    }
+}
+
+#[async_trait]
+impl SmsSender for InMemorySmsSender {
+    async fn send(&self, message: SmsMessage) -> Result<(), AppError> {
+        self.messages.lock().expect("Failed to lock sms outbox").push(message);
+        Ok(())
+    }
+}
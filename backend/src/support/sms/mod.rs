@@ -0,0 +1,36 @@
+pub mod http;
+pub mod log;
+pub mod memory;
+
+use std::sync::Arc;
+
+use axum::async_trait;
+
+use crate::infra::{env::Environment, errors::AppError};
+
+use self::{http::HttpSmsSender, log::LogSmsSender, memory::InMemorySmsSender};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmsMessage {
+    /// Phone number in E.164 format, e.g. `+5511987654321`.
+    pub to: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    async fn send(&self, message: SmsMessage) -> Result<(), AppError>;
+}
+
+pub struct SmsSenders;
+impl SmsSenders {
+    /// `SMS_SENDER` picks the implementation: `http`, `log` (the default, for local runs) or `memory`.
+    pub fn from_env() -> Result<Arc<dyn SmsSender>, AppError> {
+        let sms_sender: Arc<dyn SmsSender> = match Environment::sms_sender().as_str() {
+            "http" => Arc::new(HttpSmsSender::from_env()?),
+            "memory" => Arc::new(InMemorySmsSender::default()),
+            _ => Arc::new(LogSmsSender),
+        };
+        Ok(sms_sender)
+    }
+}
@@ -0,0 +1,102 @@
+use std::{io::ErrorKind, path::{Component, Path, PathBuf}};
+
+use axum::async_trait;
+use hyper::StatusCode;
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData, ToBusinessError}};
+
+use super::{image_not_found, ImageStorage};
+
+/// Images stored as files under `IMAGE_STORAGE_DIR`, paths are relative to it.
+pub struct LocalImageStorage {
+    dir: PathBuf,
+}
+
+impl LocalImageStorage {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(PathBuf::from(Environment::image_storage_dir()))
+    }
+
+    /// Refuses paths that would leave the storage dir.
+    fn resolve(&self, path: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(path);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(AppErrorData::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Image path is outside of the storage: {}", path),
+                None,
+            )
+            .to_business_error());
+        }
+        Ok(self.dir.join(relative))
+    }
+}
+
+#[async_trait]
+impl ImageStorage for LocalImageStorage {
+    async fn read(&self, path: &str) -> Result<Vec<u8>, AppError> {
+        match tokio::fs::read(self.resolve(path)?).await {
+            Ok(bytes) => Ok(bytes),
+            Err(err) if err.kind() == ErrorKind::NotFound => Err(image_not_found()),
+            Err(err) => Err(err.to_business_error("read image file", None)),
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.resolve(path)?).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.to_business_error("delete image file", None)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_delete_image_and_accept_it_being_gone() -> Result<(), AppError> {
+        let dir = std::env::temp_dir().join(format!("images_{}", Uuid::now_v7()));
+        std::fs::create_dir_all(dir.join("selfies")).expect("Failed to create image dir");
+        std::fs::write(dir.join("selfies/user.png"), b"png").expect("Failed to write image");
+        let storage = LocalImageStorage::new(dir.clone());
+
+        storage.delete("selfies/user.png").await?;
+        storage.delete("selfies/user.png").await?;
+
+        assert!(!dir.join("selfies/user.png").exists());
+        std::fs::remove_dir_all(dir).expect("Failed to remove image dir");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_read_image_and_return_not_found_when_missing() -> Result<(), AppError> {
+        let dir = std::env::temp_dir().join(format!("images_{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).expect("Failed to create image dir");
+        std::fs::write(dir.join("user.png"), b"png").expect("Failed to write image");
+        let storage = LocalImageStorage::new(dir.clone());
+
+        let image = storage.read("user.png").await?;
+        let missing = storage.read("missing.png").await;
+
+        assert_eq!(b"png".to_vec(), image);
+        assert!(matches!(missing, Err(AppError::Business(data)) if data.status == StatusCode::NOT_FOUND));
+        std::fs::remove_dir_all(dir).expect("Failed to remove image dir");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_return_error_when_delete_image_outside_of_storage() {
+        let storage = LocalImageStorage::new(std::env::temp_dir().join("images"));
+
+        let result = storage.delete("../etc/passwd").await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use axum::async_trait;
+
+use crate::infra::errors::AppError;
+
+use super::{image_not_found, ImageStorage};
+
+/// Keeps images in memory and records which ones were deleted, clones share the same images.
+#[derive(Clone, Default)]
+pub struct InMemoryImageStorage {
+    images: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    deleted: Arc<Mutex<Vec<String>>>,
+}
+
+impl InMemoryImageStorage {
+    pub fn put(&self, path: &str, image: Vec<u8>) {
+        self.images.lock().expect("Failed to lock image storage").insert(path.to_string(), image);
+    }
+
+    pub fn deleted(&self) -> Vec<String> {
+        self.deleted.lock().expect("Failed to lock image storage").clone()
+    }
+}
+
+#[async_trait]
+impl ImageStorage for InMemoryImageStorage {
+    async fn read(&self, path: &str) -> Result<Vec<u8>, AppError> {
+        self.images
+            .lock()
+            .expect("Failed to lock image storage")
+            .get(path)
+            .cloned()
+            .ok_or_else(image_not_found)
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), AppError> {
+        self.images.lock().expect("Failed to lock image storage").remove(path);
+        self.deleted.lock().expect("Failed to lock image storage").push(path.to_string());
+        Ok(())
+    }
+}
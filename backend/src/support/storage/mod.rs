@@ -0,0 +1,41 @@
+pub mod local;
+pub mod memory;
+
+use std::sync::Arc;
+
+use axum::async_trait;
+use hyper::StatusCode;
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData}};
+
+use self::{local::LocalImageStorage, memory::InMemoryImageStorage};
+
+/// Where the images referenced by `Biometrics::image_path` live.
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    /// Fails with `404 Image not found` when there is no image at the path.
+    async fn read(&self, path: &str) -> Result<Vec<u8>, AppError>;
+
+    /// Deleting an image that is already gone succeeds, so an interrupted purge can simply run again.
+    async fn delete(&self, path: &str) -> Result<(), AppError>;
+}
+
+pub struct ImageStorages;
+impl ImageStorages {
+    /// `IMAGE_STORAGE` picks the implementation: `local` (the default) or `memory`.
+    pub fn from_env() -> Arc<dyn ImageStorage> {
+        match Environment::image_storage().as_str() {
+            "memory" => Arc::new(InMemoryImageStorage::default()),
+            _ => Arc::new(LocalImageStorage::from_env()),
+        }
+    }
+}
+
+fn image_not_found() -> AppError {
+    AppErrorData::new(
+        StatusCode::NOT_FOUND,
+        String::from("Image not found"),
+        None,
+    )
+    .to_business_error()
+}
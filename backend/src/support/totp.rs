@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use rand::{rngs::OsRng, RngCore};
+use totp_rs::{Algorithm, TOTP};
+
+use crate::infra::env::Environment;
+
+const SECRET_BYTES: usize = 20;
+const DIGITS: usize = 6;
+const STEP_SECONDS: u64 = 30;
+/// Codes of the previous and the next step are accepted too, to tolerate clock drift on the device.
+const SKEW_STEPS: i64 = 1;
+
+/// RFC 6238 time-based one-time passwords with the parameters every authenticator app supports:
+/// SHA-1, six digits and 30 seconds steps.
+pub struct Totp {
+    totp: TOTP,
+}
+
+impl Totp {
+    pub fn generate_secret() -> Vec<u8> {
+        let mut secret = vec![0u8; SECRET_BYTES];
+        OsRng.fill_bytes(&mut secret);
+        secret
+    }
+
+    pub fn new(secret: Vec<u8>, account_name: String) -> Self {
+        let totp = TOTP::new_unchecked(
+            Algorithm::SHA1,
+            DIGITS,
+            0,
+            STEP_SECONDS,
+            secret,
+            Some(Environment::mfa_issuer()),
+            account_name,
+        );
+        Self { totp }
+    }
+
+    pub fn secret_base32(&self) -> String {
+        self.totp.get_secret_base32()
+    }
+
+    pub fn otpauth_uri(&self) -> String {
+        self.totp.get_url()
+    }
+
+    /// Returns the step the code belongs to. Steps up to `last_used_step` are skipped,
+    /// so a code that was already accepted can not be replayed.
+    pub fn verify(&self, code: &str, now: DateTime<Utc>, last_used_step: Option<i64>) -> Option<i64> {
+        let code = code.trim();
+        if code.len() != DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let current_step = now.timestamp().div_euclid(STEP_SECONDS as i64);
+        (current_step - SKEW_STEPS..=current_step + SKEW_STEPS)
+            .filter(|step| last_used_step.is_none_or(|last_used_step| *step > last_used_step))
+            .find(|step| {
+                u64::try_from(*step)
+                    .is_ok_and(|step| constant_time_eq(&self.totp.generate(step * STEP_SECONDS), code))
+            })
+    }
+}
+
+fn constant_time_eq(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::DateTime;
+
+    use crate::support::totp::Totp;
+
+    fn rfc6238_totp() -> Totp {
+        Totp::new(b"12345678901234567890".to_vec(), String::from("user@fiap.com.br"))
+    }
+
+    #[test]
+    fn should_verify_rfc6238_test_vectors() {
+        let totp = rfc6238_totp();
+
+        assert_eq!(totp.verify("287082", DateTime::from_timestamp(59, 0).unwrap(), None), Some(1));
+        assert_eq!(totp.verify("081804", DateTime::from_timestamp(1_111_111_109, 0).unwrap(), None), Some(37_037_036));
+        assert_eq!(totp.verify("005924", DateTime::from_timestamp(1_234_567_890, 0).unwrap(), None), Some(41_152_263));
+    }
+
+    #[test]
+    fn should_accept_adjacent_steps_and_reject_replayed_or_malformed_codes() {
+        let totp = rfc6238_totp();
+        let now = DateTime::from_timestamp(89, 0).unwrap();
+
+        assert_eq!(totp.verify("287082", now, None), Some(1));
+        assert_eq!(totp.verify("287082", now, Some(1)), None);
+        assert_eq!(totp.verify("287082", DateTime::from_timestamp(149, 0).unwrap(), None), None);
+        assert_eq!(totp.verify("28708", now, None), None);
+        assert_eq!(totp.verify("28708a", now, None), None);
+    }
+
+    #[test]
+    fn should_build_otpauth_uri_with_issuer_and_account() {
+        let totp = rfc6238_totp();
+
+        assert_eq!(totp.secret_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(
+            totp.otpauth_uri(),
+            "otpauth://totp/login-auth-service:user%40fiap.com.br?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=login-auth-service"
+        );
+        assert_eq!(Totp::generate_secret().len(), 20);
+    }
+}
@@ -0,0 +1,361 @@
+use ciborium::Value;
+use data_encoding::BASE64URL_NOPAD;
+use hyper::StatusCode;
+use p256::{ecdsa::{signature::Verifier, Signature, VerifyingKey}, EncodedPoint};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::infra::{env::Environment, errors::{AppError, AppErrorData}};
+
+/// COSE id of ECDSA with P-256 and SHA-256, the algorithm every platform authenticator supports.
+pub const COSE_ALGORITHM_ES256: i64 = -7;
+const COSE_KEY_TYPE: i128 = 1;
+const COSE_KEY_ALGORITHM: i128 = 3;
+const COSE_EC2_CURVE: i128 = -1;
+const COSE_EC2_X: i128 = -2;
+const COSE_EC2_Y: i128 = -3;
+const COSE_KEY_TYPE_EC2: i128 = 2;
+const COSE_CURVE_P256: i128 = 1;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+const RP_ID_HASH_LENGTH: usize = 32;
+const AAGUID_LENGTH: usize = 16;
+
+const REGISTRATION: &str = "webauthn.create";
+const AUTHENTICATION: &str = "webauthn.get";
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    ceremony: String,
+    challenge: String,
+    origin: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE encoded, as sent by the authenticator.
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: Vec<u8>,
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    /// Layout from the WebAuthn spec: rpIdHash (32), flags (1), signCount (4) and,
+    /// when the AT flag is set, aaguid (16), credentialIdLength (2), credentialId and the COSE key.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let too_short = || String::from("authenticator data is too short");
+        let header_length = RP_ID_HASH_LENGTH + 5;
+        if bytes.len() < header_length {
+            return Err(too_short());
+        }
+        let flags = bytes[RP_ID_HASH_LENGTH];
+        let sign_count = u32::from_be_bytes(
+            bytes[RP_ID_HASH_LENGTH + 1..header_length].try_into().map_err(|_| too_short())?
+        );
+        let mut attested_credential = None;
+        if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+            let credential_data = bytes.get(header_length + AAGUID_LENGTH..).ok_or_else(too_short)?;
+            let id_length = usize::from(u16::from_be_bytes(
+                credential_data.get(..2).ok_or_else(too_short)?.try_into().map_err(|_| too_short())?
+            ));
+            let credential_id = credential_data.get(2..2 + id_length).ok_or_else(too_short)?.to_vec();
+            let key_bytes = &credential_data[2 + id_length..];
+            let mut remaining = key_bytes;
+            ciborium::from_reader::<Value, _>(&mut remaining)
+                .map_err(|err| format!("credential public key is not CBOR: {}", err))?;
+            let public_key = key_bytes[..key_bytes.len() - remaining.len()].to_vec();
+            attested_credential = Some(AttestedCredential { credential_id, public_key });
+        }
+        Ok(Self {
+            rp_id_hash: bytes[..RP_ID_HASH_LENGTH].to_vec(),
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+}
+
+/// Only ES256 keys are accepted, which is what the registration options ask for.
+pub struct CosePublicKey {
+    verifying_key: VerifyingKey,
+}
+
+impl CosePublicKey {
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let value: Value = ciborium::from_reader(bytes)
+            .map_err(|err| format!("credential public key is not CBOR: {}", err))?;
+        let entries = value.as_map().ok_or("credential public key is not a COSE key")?;
+        let integer = |label: i128| entries.iter()
+            .find(|(key, _)| key.as_integer().map(i128::from) == Some(label))
+            .and_then(|(_, value)| value.as_integer())
+            .map(i128::from);
+        let bytes = |label: i128| entries.iter()
+            .find(|(key, _)| key.as_integer().map(i128::from) == Some(label))
+            .and_then(|(_, value)| value.as_bytes())
+            .filter(|bytes| bytes.len() == 32);
+        if integer(COSE_KEY_TYPE) != Some(COSE_KEY_TYPE_EC2)
+            || integer(COSE_KEY_ALGORITHM) != Some(i128::from(COSE_ALGORITHM_ES256))
+            || integer(COSE_EC2_CURVE) != Some(COSE_CURVE_P256)
+        {
+            return Err(String::from("credential public key is not ES256"));
+        }
+        let (Some(x), Some(y)) = (bytes(COSE_EC2_X), bytes(COSE_EC2_Y)) else {
+            return Err(String::from("credential public key has no P-256 coordinates"));
+        };
+        let point = EncodedPoint::from_affine_coordinates(x.as_slice().into(), y.as_slice().into(), false);
+        let verifying_key = VerifyingKey::from_encoded_point(&point)
+            .map_err(|_| String::from("credential public key is not on P-256"))?;
+        Ok(Self { verifying_key })
+    }
+
+    /// Authenticators send ES256 signatures DER encoded.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        Signature::from_der(signature)
+            .map(|signature| self.verifying_key.verify(message, &signature).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredPasskey {
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+/// Verifies the registration and authentication ceremonies for one relying party.
+/// Attestation statements are not checked, the options ask for `none` as consumer passkeys usually send.
+pub struct WebAuthn {
+    rp_id: String,
+    origins: Vec<String>,
+}
+
+impl WebAuthn {
+    pub fn new(rp_id: String, origins: Vec<String>) -> Self {
+        Self { rp_id, origins }
+    }
+
+    pub fn from_env() -> Self {
+        let origins = Environment::webauthn_origins()
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        Self::new(Environment::webauthn_rp_id(), origins)
+    }
+
+    pub fn rp_id(&self) -> &str {
+        &self.rp_id
+    }
+
+    /// The challenge the client signed, so the caller can look up the ceremony it belongs to.
+    pub fn client_challenge(client_data_json: &[u8]) -> Result<String, AppError> {
+        let client_data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| invalid(StatusCode::BAD_REQUEST, "client data is not valid JSON"))?;
+        Ok(client_data.challenge)
+    }
+
+    pub fn verify_registration(
+        &self,
+        client_data_json: &[u8],
+        attestation_object: &[u8],
+        expected_challenge: &str,
+    ) -> Result<RegisteredPasskey, AppError> {
+        let status = StatusCode::BAD_REQUEST;
+        self.verify_client_data(status, client_data_json, REGISTRATION, expected_challenge)?;
+        let attestation: Value = ciborium::from_reader(attestation_object)
+            .map_err(|_| invalid(status, "attestation object is not CBOR"))?;
+        let auth_data = attestation
+            .as_map()
+            .and_then(|entries| entries.iter().find(|(key, _)| key.as_text() == Some("authData")))
+            .and_then(|(_, value)| value.as_bytes())
+            .ok_or_else(|| invalid(status, "attestation object has no authenticator data"))?;
+        let authenticator_data = self.verify_authenticator_data(status, auth_data)?;
+        let credential = authenticator_data
+            .attested_credential
+            .ok_or_else(|| invalid(status, "authenticator data has no credential"))?;
+        CosePublicKey::parse(&credential.public_key).map_err(|reason| invalid(status, &reason))?;
+        Ok(RegisteredPasskey {
+            credential_id: credential.credential_id,
+            public_key: credential.public_key,
+            sign_count: authenticator_data.sign_count,
+        })
+    }
+
+    /// Returns the new signature counter. Authenticators that count must always move forward,
+    /// a counter that does not is a sign the credential was cloned.
+    pub fn verify_assertion(
+        &self,
+        client_data_json: &[u8],
+        authenticator_data: &[u8],
+        signature: &[u8],
+        expected_challenge: &str,
+        public_key: &[u8],
+        stored_sign_count: u32,
+    ) -> Result<u32, AppError> {
+        let status = StatusCode::UNAUTHORIZED;
+        self.verify_client_data(status, client_data_json, AUTHENTICATION, expected_challenge)?;
+        let parsed_authenticator_data = self.verify_authenticator_data(status, authenticator_data)?;
+        let public_key = CosePublicKey::parse(public_key).map_err(|reason| invalid(status, &reason))?;
+        let signed_data = [authenticator_data, Sha256::digest(client_data_json).as_slice()].concat();
+        if !public_key.verify(&signed_data, signature) {
+            return Err(invalid(status, "signature does not match"));
+        }
+        let sign_count = parsed_authenticator_data.sign_count;
+        if (sign_count != 0 || stored_sign_count != 0) && sign_count <= stored_sign_count {
+            return Err(invalid(status, "signature counter did not increase"));
+        }
+        Ok(sign_count)
+    }
+
+    fn verify_client_data(
+        &self,
+        status: StatusCode,
+        client_data_json: &[u8],
+        ceremony: &str,
+        expected_challenge: &str,
+    ) -> Result<(), AppError> {
+        let client_data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| invalid(status, "client data is not valid JSON"))?;
+        if client_data.ceremony != ceremony {
+            return Err(invalid(status, "client data is for another ceremony"));
+        }
+        if client_data.challenge != expected_challenge {
+            return Err(invalid(status, "challenge does not match"));
+        }
+        if !self.origins.contains(&client_data.origin) {
+            return Err(invalid(status, "origin is not allowed"));
+        }
+        Ok(())
+    }
+
+    /// User verification is required, so a passkey alone proves both possession and the user.
+    fn verify_authenticator_data(&self, status: StatusCode, bytes: &[u8]) -> Result<AuthenticatorData, AppError> {
+        let authenticator_data = AuthenticatorData::parse(bytes).map_err(|reason| invalid(status, &reason))?;
+        if authenticator_data.rp_id_hash != Sha256::digest(self.rp_id.as_bytes()).as_slice() {
+            return Err(invalid(status, "relying party id does not match"));
+        }
+        if authenticator_data.flags & FLAG_USER_PRESENT == 0 || authenticator_data.flags & FLAG_USER_VERIFIED == 0 {
+            return Err(invalid(status, "user was not verified"));
+        }
+        Ok(authenticator_data)
+    }
+}
+
+/// WebAuthn sends binary fields base64url encoded, some clients keep the padding.
+pub fn decode_base64url(field: &str, value: &str) -> Result<Vec<u8>, AppError> {
+    BASE64URL_NOPAD
+        .decode(value.trim_end_matches('=').as_bytes())
+        .map_err(|_| invalid(StatusCode::BAD_REQUEST, &format!("{} is not base64url", field)))
+}
+
+fn invalid(status: StatusCode, reason: &str) -> AppError {
+    AppErrorData::new(status, format!("Invalid passkey: {}", reason), None).to_business_error()
+}
+
+#[cfg(test)]
+mod test {
+    use ciborium::Value;
+    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use rand::rngs::OsRng;
+    use sha2::{Digest, Sha256};
+
+    use crate::support::webauthn::{AuthenticatorData, CosePublicKey, WebAuthn};
+
+    fn cose_key(signing_key: &SigningKey) -> Vec<u8> {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let key = Value::Map(vec![
+            (Value::Integer(1.into()), Value::Integer(2.into())),
+            (Value::Integer(3.into()), Value::Integer((-7).into())),
+            (Value::Integer((-1).into()), Value::Integer(1.into())),
+            (Value::Integer((-2).into()), Value::Bytes(point.x().expect("Expected x").to_vec())),
+            (Value::Integer((-3).into()), Value::Bytes(point.y().expect("Expected y").to_vec())),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&key, &mut bytes).expect("Failed to encode key");
+        bytes
+    }
+
+    fn authenticator_data(rp_id: &str, flags: u8, sign_count: u32) -> Vec<u8> {
+        [Sha256::digest(rp_id.as_bytes()).as_slice(), &[flags], &sign_count.to_be_bytes()].concat()
+    }
+
+    fn client_data(ceremony: &str, challenge: &str, origin: &str) -> Vec<u8> {
+        serde_json::json!({ "type": ceremony, "challenge": challenge, "origin": origin }).to_string().into_bytes()
+    }
+
+    fn sign(signing_key: &SigningKey, authenticator_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+        let signature: Signature = signing_key.sign(&[authenticator_data, Sha256::digest(client_data).as_slice()].concat());
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    fn web_authn() -> WebAuthn {
+        WebAuthn::new(String::from("fiap.com.br"), vec![String::from("https://fiap.com.br")])
+    }
+
+    #[test]
+    fn should_parse_attested_credential_from_authenticator_data() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let public_key = cose_key(&signing_key);
+        let bytes = [
+            authenticator_data("fiap.com.br", 0x45, 7).as_slice(),
+            &[0u8; 16],
+            &3u16.to_be_bytes(),
+            &[1, 2, 3],
+            &public_key,
+        ].concat();
+
+        let parsed = AuthenticatorData::parse(&bytes).expect("Failed to parse");
+
+        assert_eq!(parsed.sign_count, 7);
+        let credential = parsed.attested_credential.expect("Expected credential");
+        assert_eq!(credential.credential_id, vec![1, 2, 3]);
+        assert_eq!(credential.public_key, public_key);
+        assert!(CosePublicKey::parse(&credential.public_key).is_ok());
+        assert!(AuthenticatorData::parse(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn should_verify_assertion_signed_by_credential_key() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let authenticator_data = authenticator_data("fiap.com.br", 0x05, 2);
+        let client_data = client_data("webauthn.get", "challenge", "https://fiap.com.br");
+        let signature = sign(&signing_key, &authenticator_data, &client_data);
+
+        let sign_count = web_authn()
+            .verify_assertion(&client_data, &authenticator_data, &signature, "challenge", &cose_key(&signing_key), 1)
+            .expect("Failed to verify assertion");
+
+        assert_eq!(sign_count, 2);
+    }
+
+    #[test]
+    fn should_return_error_when_assertion_is_not_valid() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let other_key = SigningKey::random(&mut OsRng);
+        let public_key = cose_key(&signing_key);
+        let verify = |authenticator_data: Vec<u8>, client_data: Vec<u8>, signing_key: &SigningKey, stored_sign_count: u32| {
+            let signature = sign(signing_key, &authenticator_data, &client_data);
+            let result = web_authn().verify_assertion(&client_data, &authenticator_data, &signature, "challenge", &public_key, stored_sign_count);
+            format!("{:?}", result.expect_err("Expected error"))
+        };
+        let get = |origin: &str| client_data("webauthn.get", "challenge", origin);
+
+        assert!(verify(authenticator_data("fiap.com.br", 0x05, 2), get("https://evil.com"), &signing_key, 1).contains("origin is not allowed"));
+        assert!(verify(authenticator_data("evil.com", 0x05, 2), get("https://fiap.com.br"), &signing_key, 1).contains("relying party id does not match"));
+        assert!(verify(authenticator_data("fiap.com.br", 0x01, 2), get("https://fiap.com.br"), &signing_key, 1).contains("user was not verified"));
+        assert!(verify(authenticator_data("fiap.com.br", 0x05, 2), get("https://fiap.com.br"), &other_key, 1).contains("signature does not match"));
+        assert!(verify(authenticator_data("fiap.com.br", 0x05, 2), get("https://fiap.com.br"), &signing_key, 2).contains("signature counter did not increase"));
+        assert!(verify(authenticator_data("fiap.com.br", 0x05, 2), client_data("webauthn.create", "challenge", "https://fiap.com.br"), &signing_key, 1).contains("another ceremony"));
+    }
+}
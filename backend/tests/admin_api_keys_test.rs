@@ -0,0 +1,183 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use chrono::{Duration, Utc};
+    use login_auth_service::feature::admin::domain::{ApiKey, ApiKeyScope};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+    use crate::commons::{body_as_json_value, AuthCommons, BiometricsCommons, TestContext};
+
+    fn admin_request(method: Method, uri: &str, body: Option<Value>, admin_token: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .body(body.map(|body| Body::from(body.to_string())).unwrap_or_else(Body::empty))
+            .expect("Failed to build request")
+    }
+
+    fn get_biometric_request(customer_id: &Uuid, api_key: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/get/{}", customer_id))
+            .header("X-Api-Key", api_key)
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn update_biometric_request(customer_id: &Uuid, image_path: &str, status: &str, api_key: &str) -> Request<Body> {
+        let body = json!({ "customer_id": customer_id, "image_path": image_path, "status": status });
+        Request::builder()
+            .method(Method::PUT)
+            .uri(String::from("/biometrics/actions/update"))
+            .header("Content-Type", "application/json")
+            .header("X-Api-Key", api_key)
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn create_api_key(ctx: &&mut TestContext, scopes: Value) -> Value {
+        let admin_token = AuthCommons::admin_access_token(ctx).await;
+        let body = json!({ "name": "backoffice", "scopes": scopes });
+        let response = send(ctx, admin_request(Method::POST, "/admin/api-keys", Some(body), &admin_token)).await;
+        assert_eq!(StatusCode::CREATED, response.status());
+        body_as_json_value(response.into_body()).await
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_show_key_only_when_created(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+
+        let created = create_api_key(&ctx, json!(["biometrics_read"])).await;
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+        let listed = send(&ctx, admin_request(Method::GET, "/admin/api-keys", None, &admin_token)).await;
+
+        let key = created["key"].as_str().expect("Failed to read key");
+        let prefix = created["prefix"].as_str().expect("Failed to read prefix");
+        assert!(prefix.starts_with("lsk_"));
+        assert!(key.starts_with(&format!("{}_", prefix)));
+        assert_eq!(json!(["biometrics_read"]), created["scopes"]);
+        assert_eq!(StatusCode::OK, listed.status());
+        let listed = body_as_json_value(listed.into_body()).await;
+        assert_eq!(1, listed.as_array().expect("Failed to read list").len());
+        assert_eq!(created["id"], listed[0]["id"]);
+        assert_eq!(json!(prefix), listed[0]["prefix"]);
+        assert!(listed[0].get("key").is_none());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_read_biometrics_of_any_customer_with_read_scope_only(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let created = create_api_key(&ctx, json!(["biometrics_read"])).await;
+        let key = created["key"].as_str().expect("Failed to read key");
+
+        let read = send(&ctx, get_biometric_request(&customer.id, key)).await;
+        let update = send(&ctx, update_biometric_request(&customer.id, "s3://image", "conclued", key)).await;
+
+        assert_eq!(StatusCode::OK, read.status());
+        let body = body_as_json_value(read.into_body()).await;
+        assert_eq!(json!(customer.id.to_string()), body["customerId"]);
+        assert_eq!(StatusCode::FORBIDDEN, update.status());
+        let mut transaction = ctx.app_state.begin_transaction().await.expect("Failed to create transaction");
+        let api_keys = ApiKey::find_all(&mut transaction).await.expect("Failed to find api keys");
+        assert!(api_keys[0].last_used_at.is_some());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_change_status_but_not_image_with_review_scope(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let created = create_api_key(&ctx, json!(["biometrics_review"])).await;
+        let key = created["key"].as_str().expect("Failed to read key");
+
+        let conclued = send(&ctx, update_biometric_request(&customer.id, "s3://image", "conclued", key)).await;
+        let replaced = send(&ctx, update_biometric_request(&customer.id, "s3://other-image", "conclued", key)).await;
+
+        assert_eq!(StatusCode::OK, conclued.status());
+        assert_eq!(json!("conclued"), body_as_json_value(conclued.into_body()).await["status"]);
+        assert_eq!(StatusCode::FORBIDDEN, replaced.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_reject_revoked_expired_and_unknown_keys(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let created = create_api_key(&ctx, json!(["biometrics_read"])).await;
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+        let revoke_uri = format!("/admin/api-keys/{}", created["id"].as_str().expect("Failed to read id"));
+        let revoked = send(&ctx, admin_request(Method::DELETE, &revoke_uri, None, &admin_token)).await;
+        let revoked_again = send(&ctx, admin_request(Method::DELETE, &revoke_uri, None, &admin_token)).await;
+        let (expired_key, expired_plain_key) = ApiKey::new(String::from("expired"), vec![ApiKeyScope::BiometricsRead], Some(Utc::now() - Duration::minutes(1)));
+        let mut transaction = ctx.app_state.begin_transaction().await.expect("Failed to create transaction");
+        ApiKey::insert(&mut transaction, expired_key).await.expect("Failed to insert api key");
+        ctx.app_state.commit_transaction(transaction).await.expect("Failed to commit transaction");
+
+        let with_revoked = send(&ctx, get_biometric_request(&customer.id, created["key"].as_str().expect("Failed to read key"))).await;
+        let with_expired = send(&ctx, get_biometric_request(&customer.id, &expired_plain_key)).await;
+        let with_unknown = send(&ctx, get_biometric_request(&customer.id, "lsk_unknown_key")).await;
+
+        assert_eq!(StatusCode::NO_CONTENT, revoked.status());
+        assert_eq!(StatusCode::NOT_FOUND, revoked_again.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, with_revoked.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, with_expired.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, with_unknown.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_api_key_request_is_invalid(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+        let body = json!({ "name": " ", "scopes": [], "expiresAt": "2000-01-01T00:00:00Z" });
+
+        let response = send(&ctx, admin_request(Method::POST, "/admin/api-keys", Some(body), &admin_token)).await;
+        let without_admin = send(&ctx, Request::builder()
+            .method(Method::GET)
+            .uri("/admin/api-keys")
+            .body(Body::empty())
+            .expect("Failed to build request")).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(json!("invalid fields: expiresAt, name, scopes"), body["message"]);
+        assert_eq!(StatusCode::UNAUTHORIZED, without_admin.status());
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use login_auth_service::feature::auth::domain::Role;
+    use login_auth_service::support::jwt::{AuthorizationClaims, Jwt};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    fn build_request(customer_id: &Uuid, roles: Value, token: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::PUT)
+            .uri(format!("/admin/customers/{}/roles", customer_id))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::from(json!({ "roles": roles }).to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_emit_roles_granted_by_admin_in_access_token(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("reviewer@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let before = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+
+        let response = send(&ctx, build_request(&customer.id, json!(["reviewer", "customer"]), &admin_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["roles"], json!(["customer", "reviewer"]));
+        let after = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let claims_before = AuthorizationClaims::extract_jwt(before.access_token, &ctx.app_state.jwt_key_ring).expect("Failed to extract claims");
+        let claims_after = AuthorizationClaims::extract_jwt(after.access_token, &ctx.app_state.jwt_key_ring).expect("Failed to extract claims");
+        assert_eq!(vec![Role::Customer], claims_before.roles);
+        assert_eq!(vec![Role::Customer, Role::Reviewer], claims_after.roles);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_allow_accounts_with_admin_role_only(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let password = String::from("my$ecr3T");
+        let admin_email = String::from("admin@fiap.com.br");
+        let customer_email = String::from("user@fiap.com.br");
+        let admin = AuthCommons::craete_customer(&ctx, &admin_email, &password).await;
+        let customer = AuthCommons::craete_customer(&ctx, &customer_email, &password).await;
+        AuthCommons::grant_roles(&ctx, &admin, &[Role::Admin]).await;
+        let admin_tokens = AuthCommons::sing_in(&ctx, &admin_email, &password).await;
+        let customer_tokens = AuthCommons::sing_in(&ctx, &customer_email, &password).await;
+
+        let by_customer = send(&ctx, build_request(&admin.id, json!(["customer"]), &customer_tokens.access_token)).await;
+        let by_admin = send(&ctx, build_request(&customer.id, json!(["customer", "reviewer"]), &admin_tokens.access_token)).await;
+        let by_nobody = send(&ctx, build_request(&customer.id, json!(["admin"]), "not-a-token")).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, by_customer.status());
+        assert_eq!(StatusCode::OK, by_admin.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, by_nobody.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_no_role_is_given(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("my$ecr3T")).await;
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+
+        let response = send(&ctx, build_request(&customer.id, json!([]), &admin_token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["errors"]["roles"], json!(["at least one role is expected"]));
+        Ok(())
+    }
+}
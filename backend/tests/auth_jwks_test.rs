@@ -0,0 +1,45 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use jsonwebtoken::decode_header;
+    use rstest::rstest;
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, validate_json_path, AuthCommons, TestContext};
+
+    fn build_request() -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(String::from("/.well-known/jwks.json"))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_public_keys_matching_issued_jwt_key_id(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = ctx.app.clone().oneshot(build_request()).await?;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body_json = body_as_json_value(response.into_body()).await;
+        let kid = decode_header(&tokens.access_token)?.kid.expect("Expected kid header");
+        validate_json_path(body_json.clone(), "keys[0].kid", &kid);
+        validate_json_path(body_json.clone(), "keys[0].kty", "RSA");
+        validate_json_path(body_json.clone(), "keys[0].alg", "RS256");
+        validate_json_path(body_json, "keys[0].use", "sig");
+        Ok(())
+    }
+}
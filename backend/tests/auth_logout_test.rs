@@ -0,0 +1,157 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use login_auth_service::feature::auth::domain::Customer;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{AuthCommons, TestContext};
+
+    fn build_request(body: Value, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/logout"))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_get_biometric_request(customer: &Customer, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/get/{}", customer.id))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn build_refresh_request(refresh_token: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/refresh"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "refreshToken": refresh_token }).to_string()))
+            .expect("Failed to build request")
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_access_and_refresh_token_when_logout(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let before_logout = ctx.app.clone().oneshot(build_get_biometric_request(&customer, &tokens.access_token)).await?;
+
+        let response = ctx.app.clone().oneshot(build_request(json!({ "refreshToken": tokens.refresh_token }), &tokens.access_token)).await?;
+
+        assert_eq!(StatusCode::NOT_FOUND, before_logout.status());
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let after_logout = ctx.app.clone().oneshot(build_get_biometric_request(&customer, &tokens.access_token)).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, after_logout.status());
+        let refresh = ctx.app.clone().oneshot(build_refresh_request(&tokens.refresh_token)).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, refresh.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_access_token_when_logout_with_unknown_refresh_token(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = ctx.app.clone().oneshot(build_request(json!({ "refreshToken": "unknown-refresh-token" }), &tokens.access_token)).await?;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let after_logout = ctx.app.clone().oneshot(build_get_biometric_request(&customer, &tokens.access_token)).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, after_logout.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_other_devices_when_logout(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let tablet = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = ctx.app.clone().oneshot(build_request(json!({}), &phone.access_token)).await?;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let tablet_response = ctx.app.clone().oneshot(build_get_biometric_request(&customer, &tablet.access_token)).await?;
+        assert_eq!(StatusCode::NOT_FOUND, tablet_response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_every_device_when_logout_everywhere(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let lost_tablet = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = ctx.app.clone().oneshot(build_request(json!({ "everywhere": true }), &phone.access_token)).await?;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let tablet_response = ctx.app.clone().oneshot(build_get_biometric_request(&customer, &lost_tablet.access_token)).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, tablet_response.status());
+        let tablet_refresh = ctx.app.clone().oneshot(build_refresh_request(&lost_tablet.refresh_token)).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, tablet_refresh.status());
+        let signed_in_again = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let sessions = Request::builder()
+            .method(Method::GET)
+            .uri(String::from("/auth/sessions"))
+            .header("Authorization", format!("Bearer {}", signed_in_again.access_token))
+            .body(Body::empty())?;
+        assert_eq!(StatusCode::OK, ctx.app.clone().oneshot(sessions).await?.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_logout_without_authorization(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/logout"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({}).to_string()))
+            .expect("Failed to build request");
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+}
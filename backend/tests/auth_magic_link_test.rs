@@ -0,0 +1,131 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    fn build_request(uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    /// The magic link mail is sent in the background, after the request response.
+    async fn magic_link_token(ctx: &&mut TestContext, email: &str) -> String {
+        for _ in 0..50 {
+            if let Some(message) = ctx.mail_sender.last_message_to(email) {
+                return message.body.split("?token=").nth(1).expect("Expected a token in the mail")
+                    .lines().next().expect("Expected a token line").trim().to_string();
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Expected a magic link mail to {}", email);
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_sing_in_when_consume_magic_link(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &String::from("my$ecr3T")).await;
+        let request = ctx.app.clone().oneshot(build_request("/auth/magic-link", json!({ "email": " User@Fiap.com.br " }))).await?;
+        let token = magic_link_token(&ctx, &email).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/magic-link/consume", json!({ "token": token }))).await?;
+
+        assert_eq!(StatusCode::ACCEPTED, request.status());
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("Authorization").is_some());
+        assert!(response.headers().get("Refresh-Token").is_some());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["id"], json!(customer.id));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_consume_magic_link_twice(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        AuthCommons::craete_customer(&ctx, &email, &String::from("my$ecr3T")).await;
+        ctx.app.clone().oneshot(build_request("/auth/magic-link", json!({ "email": email }))).await?;
+        let token = magic_link_token(&ctx, &email).await;
+        let first = ctx.app.clone().oneshot(build_request("/auth/magic-link/consume", json!({ "token": token }))).await?;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/magic-link/consume", json!({ "token": token }))).await?;
+
+        assert_eq!(StatusCode::OK, first.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_consume_access_token_as_magic_link(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &String::from("my$ecr3T")).await;
+        let access_token = AuthCommons::generate_jwt(&customer);
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/magic-link/consume", json!({ "token": access_token }))).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_accept_magic_link_request_for_unknown_email_without_mail(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = ctx.app.clone().oneshot(build_request("/auth/magic-link", json!({ "email": "nobody@fiap.com.br" }))).await?;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(ctx.mail_sender.last_message_to("nobody@fiap.com.br").is_none());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_mail_another_magic_link_before_resend_interval(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        AuthCommons::craete_customer(&ctx, &email, &String::from("my$ecr3T")).await;
+        ctx.app.clone().oneshot(build_request("/auth/magic-link", json!({ "email": email }))).await?;
+        magic_link_token(&ctx, &email).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/magic-link", json!({ "email": email }))).await?;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert_eq!(1, ctx.mail_sender.messages().iter().filter(|message| message.to == email).count());
+        Ok(())
+    }
+}
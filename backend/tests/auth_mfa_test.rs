@@ -0,0 +1,274 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use login_auth_service::feature::auth::domain::Customer;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use totp_rs::TOTP;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    fn build_request(uri: &str, body: Value, jwt: Option<&String>) -> Request<Body> {
+        let builder = Request::builder()
+            .method(Method::POST)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json");
+        let builder = match jwt {
+            Some(jwt) => builder.header("Authorization", format!("Bearer {}", jwt)),
+            None => builder,
+        };
+        builder
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_get_biometric_request(customer: &Customer, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/get/{}", customer.id))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn code_at(totp: &TOTP, steps_ahead: u64) -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Failed to read clock").as_secs();
+        totp.generate(now + steps_ahead * 30)
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    const PASSWORD: &str = "my$ecr3T";
+
+    fn recovery_codes_of(body: &Value) -> Vec<String> {
+        body["recoveryCodes"]
+            .as_array()
+            .expect("Expected recoveryCodes")
+            .iter()
+            .map(|code| code.as_str().expect("Expected code").to_string())
+            .collect()
+    }
+
+    async fn enroll(ctx: &&mut TestContext, jwt: &String) -> (TOTP, Vec<String>) {
+        let response = send(ctx, build_request("/auth/mfa/enroll", json!({ "currentPassword": PASSWORD }), Some(jwt))).await;
+        assert_eq!(StatusCode::CREATED, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        let otpauth_uri = body["otpauthUri"].as_str().expect("Expected otpauthUri");
+        let totp = TOTP::from_url(otpauth_uri).expect("Failed to parse otpauth uri");
+        assert_eq!(body["secret"].as_str(), Some(totp.get_secret_base32().as_str()));
+        (totp, recovery_codes_of(&body))
+    }
+
+    async fn enable_mfa(ctx: &&mut TestContext, jwt: &String) -> (TOTP, Vec<String>) {
+        let (totp, recovery_codes) = enroll(ctx, jwt).await;
+        let response = send(ctx, build_request("/auth/mfa/confirm", json!({ "code": code_at(&totp, 0), "currentPassword": PASSWORD }), Some(jwt))).await;
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        (totp, recovery_codes)
+    }
+
+    async fn challenge(ctx: &&mut TestContext, email: &String, password: &String) -> String {
+        let response = send(ctx, build_request("/auth/singin", json!({ "email": email, "password": password }), None)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("Authorization").is_none());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["mfaRequired"], json!(true));
+        body["challengeToken"].as_str().expect("Expected challengeToken").to_string()
+    }
+
+    async fn verify(ctx: &&mut TestContext, challenge_token: &String, code: &String) -> Response {
+        send(ctx, build_request("/auth/mfa/verify", json!({ "challengeToken": challenge_token, "code": code }), None)).await
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_require_totp_code_on_sing_in_after_mfa_is_enabled(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let (totp, recovery_codes) = enable_mfa(&ctx, &tokens.access_token).await;
+        let challenge_token = challenge(&ctx, &email, &password).await;
+
+        let response = verify(&ctx, &challenge_token, &code_at(&totp, 1)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(10, recovery_codes.len());
+        let access_token = response.headers().get("Authorization").expect("Expected Authorization").to_str()?.to_string();
+        assert!(response.headers().get("Refresh-Token").is_some());
+        let biometrics = send(&ctx, build_get_biometric_request(&customer, &access_token)).await;
+        assert_eq!(StatusCode::NOT_FOUND, biometrics.status());
+        let challenge_as_access = send(&ctx, build_get_biometric_request(&customer, &challenge_token)).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, challenge_as_access.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_verify_with_invalid_or_replayed_code(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let (totp, _) = enable_mfa(&ctx, &tokens.access_token).await;
+        let challenge_token = challenge(&ctx, &email, &password).await;
+
+        let invalid = verify(&ctx, &challenge_token, &String::from("000000")).await;
+        let replayed = verify(&ctx, &challenge_token, &code_at(&totp, 0)).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, invalid.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, replayed.status());
+        assert_eq!(StatusCode::OK, verify(&ctx, &challenge_token, &code_at(&totp, 1)).await.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_accept_each_recovery_code_and_challenge_only_once(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let (_, recovery_codes) = enable_mfa(&ctx, &tokens.access_token).await;
+        let first_challenge = challenge(&ctx, &email, &password).await;
+        let second_challenge = challenge(&ctx, &email, &password).await;
+
+        let response = verify(&ctx, &first_challenge, &recovery_codes[0].to_uppercase()).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, verify(&ctx, &second_challenge, &recovery_codes[0]).await.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, verify(&ctx, &first_challenge, &recovery_codes[1]).await.status());
+        assert_eq!(StatusCode::OK, verify(&ctx, &second_challenge, &recovery_codes[1]).await.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_sing_in_without_challenge_until_enrollment_is_confirmed(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        enroll(&ctx, &tokens.access_token).await;
+
+        let invalid_confirm = send(&ctx, build_request("/auth/mfa/confirm", json!({ "code": "000000", "currentPassword": PASSWORD }), Some(&tokens.access_token))).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, invalid_confirm.status());
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        assert!(!tokens.access_token.is_empty());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_enroll_with_mfa_already_enabled(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        enable_mfa(&ctx, &tokens.access_token).await;
+
+        let response = send(&ctx, build_request("/auth/mfa/enroll", json!({ "currentPassword": PASSWORD }), Some(&tokens.access_token))).await;
+
+        assert_eq!(StatusCode::CONFLICT, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_enroll_with_wrong_current_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from(PASSWORD);
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = send(&ctx, build_request("/auth/mfa/enroll", json!({ "currentPassword": "wr0ng$ecreT" }), Some(&tokens.access_token))).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["errors"]["currentPassword"], json!(["current password does not match"]));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_sing_in_without_challenge_after_mfa_is_disabled(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from(PASSWORD);
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let (totp, _) = enable_mfa(&ctx, &tokens.access_token).await;
+        let without_code = send(&ctx, build_request("/auth/mfa/disable", json!({ "code": "000000", "currentPassword": PASSWORD }), Some(&tokens.access_token))).await;
+
+        let response = send(&ctx, build_request("/auth/mfa/disable", json!({ "code": code_at(&totp, 1), "currentPassword": PASSWORD }), Some(&tokens.access_token))).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, without_code.status());
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        assert!(!tokens.access_token.is_empty());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_replace_recovery_codes_when_regenerated(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from(PASSWORD);
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let (_, old_recovery_codes) = enable_mfa(&ctx, &tokens.access_token).await;
+        let wrong_password = send(&ctx, build_request("/auth/mfa/recovery-codes", json!({ "code": old_recovery_codes[1], "currentPassword": "wr0ng$ecreT" }), Some(&tokens.access_token))).await;
+
+        let response = send(&ctx, build_request("/auth/mfa/recovery-codes", json!({ "code": old_recovery_codes[0], "currentPassword": PASSWORD }), Some(&tokens.access_token))).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, wrong_password.status());
+        assert_eq!(StatusCode::CREATED, response.status());
+        let recovery_codes = recovery_codes_of(&body_as_json_value(response.into_body()).await);
+        assert_eq!(10, recovery_codes.len());
+        let challenge_token = challenge(&ctx, &email, &password).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, verify(&ctx, &challenge_token, &old_recovery_codes[1]).await.status());
+        assert_eq!(StatusCode::OK, verify(&ctx, &challenge_token, &recovery_codes[0]).await.status());
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use ciborium::Value as Cbor;
+    use data_encoding::BASE64URL_NOPAD;
+    use login_auth_service::feature::auth::domain::Customer;
+    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use rand::rngs::OsRng;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use sha2::{Digest, Sha256};
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    const RP_ID: &str = "localhost";
+    const ORIGIN: &str = "http://localhost:8080";
+    const USER_PRESENT_AND_VERIFIED: u8 = 0x05;
+    const ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+    /// Plays the part of a platform authenticator holding a single ES256 passkey.
+    struct SoftAuthenticator {
+        credential_id: Vec<u8>,
+        signing_key: SigningKey,
+        sign_count: u32,
+    }
+
+    impl SoftAuthenticator {
+        fn new() -> Self {
+            Self {
+                credential_id: uuid::Uuid::now_v7().as_bytes().to_vec(),
+                signing_key: SigningKey::random(&mut OsRng),
+                sign_count: 0,
+            }
+        }
+
+        fn id(&self) -> String {
+            BASE64URL_NOPAD.encode(&self.credential_id)
+        }
+
+        fn client_data(ceremony: &str, challenge: &str) -> Vec<u8> {
+            json!({ "type": ceremony, "challenge": challenge, "origin": ORIGIN }).to_string().into_bytes()
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let point = self.signing_key.verifying_key().to_encoded_point(false);
+            let key = Cbor::Map(vec![
+                (Cbor::Integer(1.into()), Cbor::Integer(2.into())),
+                (Cbor::Integer(3.into()), Cbor::Integer((-7).into())),
+                (Cbor::Integer((-1).into()), Cbor::Integer(1.into())),
+                (Cbor::Integer((-2).into()), Cbor::Bytes(point.x().expect("Expected x").to_vec())),
+                (Cbor::Integer((-3).into()), Cbor::Bytes(point.y().expect("Expected y").to_vec())),
+            ]);
+            let mut bytes = Vec::new();
+            ciborium::into_writer(&key, &mut bytes).expect("Failed to encode key");
+            bytes
+        }
+
+        fn create(&self, challenge: &str) -> Value {
+            let auth_data = [
+                Sha256::digest(RP_ID.as_bytes()).as_slice(),
+                &[USER_PRESENT_AND_VERIFIED | ATTESTED_CREDENTIAL_DATA],
+                &self.sign_count.to_be_bytes(),
+                &[0; 16],
+                &(self.credential_id.len() as u16).to_be_bytes(),
+                &self.credential_id,
+                &self.cose_key(),
+            ].concat();
+            let attestation = Cbor::Map(vec![
+                (Cbor::Text(String::from("fmt")), Cbor::Text(String::from("none"))),
+                (Cbor::Text(String::from("attStmt")), Cbor::Map(vec![])),
+                (Cbor::Text(String::from("authData")), Cbor::Bytes(auth_data)),
+            ]);
+            let mut attestation_object = Vec::new();
+            ciborium::into_writer(&attestation, &mut attestation_object).expect("Failed to encode attestation");
+            json!({
+                "id": self.id(),
+                "response": {
+                    "clientDataJSON": BASE64URL_NOPAD.encode(&Self::client_data("webauthn.create", challenge)),
+                    "attestationObject": BASE64URL_NOPAD.encode(&attestation_object),
+                }
+            })
+        }
+
+        fn get(&mut self, challenge: &str, customer: &Customer) -> Value {
+            self.sign_count += 1;
+            let auth_data = [
+                Sha256::digest(RP_ID.as_bytes()).as_slice(),
+                &[USER_PRESENT_AND_VERIFIED],
+                &self.sign_count.to_be_bytes(),
+            ].concat();
+            let client_data = Self::client_data("webauthn.get", challenge);
+            let signature: Signature = self.signing_key.sign(&[auth_data.as_slice(), Sha256::digest(&client_data).as_slice()].concat());
+            json!({
+                "id": self.id(),
+                "response": {
+                    "clientDataJSON": BASE64URL_NOPAD.encode(&client_data),
+                    "authenticatorData": BASE64URL_NOPAD.encode(&auth_data),
+                    "signature": BASE64URL_NOPAD.encode(signature.to_der().as_bytes()),
+                    "userHandle": BASE64URL_NOPAD.encode(customer.id.as_bytes()),
+                }
+            })
+        }
+    }
+
+    fn build_request(uri: &str, body: Value, jwt: Option<&String>) -> Request<Body> {
+        let builder = Request::builder()
+            .method(Method::POST)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json");
+        let builder = match jwt {
+            Some(jwt) => builder.header("Authorization", format!("Bearer {}", jwt)),
+            None => builder,
+        };
+        builder
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn challenge(ctx: &&mut TestContext, uri: &str, jwt: Option<&String>) -> Value {
+        let response = send(ctx, build_request(uri, json!({}), jwt)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        body_as_json_value(response.into_body()).await
+    }
+
+    async fn register(ctx: &&mut TestContext, authenticator: &SoftAuthenticator, jwt: &String) -> Response {
+        let options = challenge(ctx, "/auth/passkey/register/options", Some(jwt)).await;
+        let challenge = options["challenge"].as_str().expect("Expected challenge");
+        send(ctx, build_request("/auth/passkey/register", authenticator.create(challenge), Some(jwt))).await
+    }
+
+    async fn authenticate(ctx: &&mut TestContext, authenticator: &mut SoftAuthenticator, customer: &Customer) -> Response {
+        let options = challenge(ctx, "/auth/passkey/authenticate/options", None).await;
+        let challenge = options["challenge"].as_str().expect("Expected challenge");
+        send(ctx, build_request("/auth/passkey/authenticate", authenticator.get(challenge, customer), None)).await
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_sing_in_with_passkey_after_registering_it(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let mut authenticator = SoftAuthenticator::new();
+        let options = challenge(&ctx, "/auth/passkey/register/options", Some(&tokens.access_token)).await;
+
+        let registered = send(&ctx, build_request(
+            "/auth/passkey/register",
+            authenticator.create(options["challenge"].as_str().expect("Expected challenge")),
+            Some(&tokens.access_token),
+        )).await;
+        let response = authenticate(&ctx, &mut authenticator, &customer).await;
+
+        assert_eq!(options["rp"]["id"], json!(RP_ID));
+        assert_eq!(options["user"]["id"], json!(BASE64URL_NOPAD.encode(customer.id.as_bytes())));
+        assert_eq!(options["pubKeyCredParams"][0]["alg"], json!(-7));
+        assert_eq!(StatusCode::CREATED, registered.status());
+        assert_eq!(body_as_json_value(registered.into_body()).await["credentialId"], json!(authenticator.id()));
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("Authorization").is_some());
+        assert!(response.headers().get("Refresh-Token").is_some());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_list_registered_passkeys_and_refuse_registering_them_twice(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let authenticator = SoftAuthenticator::new();
+        assert_eq!(StatusCode::CREATED, register(&ctx, &authenticator, &tokens.access_token).await.status());
+
+        let options = challenge(&ctx, "/auth/passkey/register/options", Some(&tokens.access_token)).await;
+        let response = register(&ctx, &authenticator, &tokens.access_token).await;
+
+        assert_eq!(options["excludeCredentials"], json!([{ "type": "public-key", "id": authenticator.id() }]));
+        assert_eq!(StatusCode::CONFLICT, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_assertion_is_replayed(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let mut authenticator = SoftAuthenticator::new();
+        register(&ctx, &authenticator, &tokens.access_token).await;
+        let options = challenge(&ctx, "/auth/passkey/authenticate/options", None).await;
+        let assertion = authenticator.get(options["challenge"].as_str().expect("Expected challenge"), &customer);
+
+        let first = send(&ctx, build_request("/auth/passkey/authenticate", assertion.clone(), None)).await;
+        let replayed = send(&ctx, build_request("/auth/passkey/authenticate", assertion, None)).await;
+
+        assert_eq!(StatusCode::OK, first.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, replayed.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_assertion_is_signed_by_another_key(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let authenticator = SoftAuthenticator::new();
+        register(&ctx, &authenticator, &tokens.access_token).await;
+        let mut impostor = SoftAuthenticator {
+            credential_id: authenticator.credential_id.clone(),
+            signing_key: SigningKey::random(&mut OsRng),
+            sign_count: 0,
+        };
+
+        let response = authenticate(&ctx, &mut impostor, &customer).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["message"], json!("Invalid passkey: signature does not match"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_register_passkey_without_authorization(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = send(&ctx, build_request("/auth/passkey/register/options", json!({}), None)).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{AuthCommons, TestContext};
+
+    fn build_request(uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    /// The reset mail is sent in the background, after the forgot response.
+    async fn reset_token(ctx: &&mut TestContext, email: &str) -> String {
+        for _ in 0..50 {
+            if let Some(message) = ctx.mail_sender.last_message_to(email) {
+                return message.body.split("?token=").nth(1).expect("Expected a token in the mail")
+                    .lines().next().expect("Expected a token line").trim().to_string();
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Expected a reset mail to {}", email);
+    }
+
+    async fn sing_in_status(ctx: &&mut TestContext, email: &String, password: &String) -> StatusCode {
+        let request = build_request("/auth/singin", json!({ "email": email, "password": password }));
+        ctx.app.clone().oneshot(request).await.expect("Failed to sing in").status()
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_change_password_and_revoke_tokens_when_reset_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let new_password = String::from("n3w$ecreT");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let forgot = ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        let token = reset_token(&ctx, &email).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/password/reset", json!({ "token": token, "password": new_password }))).await?;
+
+        assert_eq!(StatusCode::ACCEPTED, forgot.status());
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, sing_in_status(&ctx, &email, &password).await);
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &new_password).await);
+        let refresh = ctx.app.clone().oneshot(build_request("/auth/refresh", json!({ "refreshToken": tokens.refresh_token }))).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, refresh.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_same_response_when_forgot_password_for_unknown_email(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+
+        let known = ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        let unknown = ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": "unknown@fiap.com.br" }))).await?;
+
+        assert_eq!(known.status(), unknown.status());
+        let known_body = axum::body::to_bytes(known.into_body(), usize::MAX).await?;
+        let unknown_body = axum::body::to_bytes(unknown.into_body(), usize::MAX).await?;
+        assert_eq!(known_body, unknown_body);
+        reset_token(&ctx, &email).await;
+        assert!(ctx.mail_sender.last_message_to("unknown@fiap.com.br").is_none());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_reset_token_is_used_twice(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        let token = reset_token(&ctx, &email).await;
+        ctx.app.clone().oneshot(build_request("/auth/password/reset", json!({ "token": token, "password": "n3w$ecreT" }))).await?;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/password/reset", json!({ "token": token, "password": "an0ther$ecreT" }))).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &String::from("n3w$ecreT")).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_reset_password_is_weak(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        let token = reset_token(&ctx, &email).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/password/reset", json!({ "token": token, "password": "1" }))).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_mail_another_reset_link_before_resend_interval(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        AuthCommons::craete_customer(&ctx, &email, &String::from("my$ecr3T")).await;
+        ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        reset_token(&ctx, &email).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/password/forgot", json!({ "email": email }))).await?;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert_eq!(1, ctx.mail_sender.messages().iter().filter(|message| message.to == email).count());
+        Ok(())
+    }
+}
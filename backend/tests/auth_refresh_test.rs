@@ -0,0 +1,102 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{AuthCommons, TestContext};
+
+    fn build_request(body: Value) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/refresh"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_request_body(refresh_token: &String) -> Value {
+        json!({
+            "refreshToken": refresh_token
+        })
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_new_tokens_when_refresh(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let refresh_token = AuthCommons::sing_in(&ctx, &email, &password).await.refresh_token;
+        let request = build_request(build_request_body(&refresh_token));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("Authorization").is_some());
+        let rotated_refresh_token = response.headers().get("Refresh-Token").expect("Failed to read refresh token");
+        assert_ne!(refresh_token, rotated_refresh_token.to_str()?);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_token_family_when_refresh_token_is_reused(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let refresh_token = AuthCommons::sing_in(&ctx, &email, &password).await.refresh_token;
+        let response = ctx.app.clone().oneshot(build_request(build_request_body(&refresh_token))).await?;
+        let rotated_refresh_token = response.headers().get("Refresh-Token").expect("Failed to read refresh token").to_str()?.to_string();
+
+        let reused_response = ctx.app.clone().oneshot(build_request(build_request_body(&refresh_token))).await?;
+        let rotated_response = ctx.app.clone().oneshot(build_request(build_request_body(&rotated_refresh_token))).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, reused_response.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, rotated_response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_refresh_token_is_unknown(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let request = build_request(build_request_body(&String::from("unknown")));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_refresh_token_is_empty(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let request = build_request(build_request_body(&String::from("")));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use login_auth_service::feature::auth::domain::Customer;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+    use crate::commons::{body_as_json_value, AuthCommons, SingInTokens, TestContext};
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn sing_in_on(ctx: &&mut TestContext, email: &String, password: &String, device_name: &str) -> SingInTokens {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "LoginApp/1.0")
+            .header("X-Device-Name", device_name)
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request");
+        let response = send(ctx, request).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let header = |name: &str| response.headers().get(name).expect("Expected header").to_str().expect("Failed to parse header").to_string();
+        SingInTokens {
+            access_token: header("Authorization"),
+            refresh_token: header("Refresh-Token"),
+        }
+    }
+
+    fn build_list_request(jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(String::from("/auth/sessions"))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn build_revoke_request(session_id: &str, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::DELETE)
+            .uri(format!("/auth/sessions/{}", session_id))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn build_refresh_request(refresh_token: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/refresh"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "refreshToken": refresh_token }).to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_get_biometric_request(customer: &Customer, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/get/{}", customer.id))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    async fn list(ctx: &&mut TestContext, jwt: &String) -> Vec<Value> {
+        let response = send(ctx, build_list_request(jwt)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        body_as_json_value(response.into_body()).await.as_array().expect("Expected sessions").clone()
+    }
+
+    fn session_on<'a>(sessions: &'a [Value], device_name: &str) -> &'a Value {
+        sessions
+            .iter()
+            .find(|session| session["deviceName"] == json!(device_name))
+            .expect("Expected session of the device")
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_list_a_session_per_device_when_sing_in(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = sing_in_on(&ctx, &email, &password, "Phone").await;
+        sing_in_on(&ctx, &email, &password, "Tablet").await;
+
+        let sessions = list(&ctx, &phone.access_token).await;
+
+        assert_eq!(2, sessions.len());
+        let phone_session = session_on(&sessions, "Phone");
+        assert_eq!(phone_session["current"], json!(true));
+        assert_eq!(phone_session["userAgent"], json!("LoginApp/1.0"));
+        assert!(phone_session["ipAddress"].is_string());
+        assert!(phone_session["createdAt"].is_string());
+        assert!(phone_session["lastSeenAt"].is_string());
+        assert_eq!(session_on(&sessions, "Tablet")["current"], json!(false));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_reject_tokens_of_session_when_revoked(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = sing_in_on(&ctx, &email, &password, "Phone").await;
+        let lost_tablet = sing_in_on(&ctx, &email, &password, "Tablet").await;
+        let sessions = list(&ctx, &phone.access_token).await;
+        let tablet_session_id = session_on(&sessions, "Tablet")["id"].as_str().expect("Expected id").to_string();
+
+        let response = send(&ctx, build_revoke_request(&tablet_session_id, &phone.access_token)).await;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let tablet_access = send(&ctx, build_get_biometric_request(&customer, &lost_tablet.access_token)).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, tablet_access.status());
+        let tablet_refresh = send(&ctx, build_refresh_request(&lost_tablet.refresh_token)).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, tablet_refresh.status());
+        let phone_access = send(&ctx, build_get_biometric_request(&customer, &phone.access_token)).await;
+        assert_eq!(StatusCode::NOT_FOUND, phone_access.status());
+        let sessions = list(&ctx, &phone.access_token).await;
+        assert_eq!(1, sessions.len());
+        assert_eq!(sessions[0]["deviceName"], json!("Phone"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_session_when_refresh(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = sing_in_on(&ctx, &email, &password, "Phone").await;
+
+        let response = send(&ctx, build_refresh_request(&phone.refresh_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let access_token = response.headers().get("Authorization").expect("Expected Authorization").to_str()?.to_string();
+        let sessions = list(&ctx, &access_token).await;
+        assert_eq!(1, sessions.len());
+        assert_eq!(sessions[0]["current"], json!(true));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_not_found_when_revoke_session_of_another_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let password = String::from("my$ecr3T");
+        let owner_email = String::from("owner@fiap.com.br");
+        let intruder_email = String::from("intruder@fiap.com.br");
+        AuthCommons::craete_customer(&ctx, &owner_email, &password).await;
+        AuthCommons::craete_customer(&ctx, &intruder_email, &password).await;
+        let owner = sing_in_on(&ctx, &owner_email, &password, "Phone").await;
+        let intruder = sing_in_on(&ctx, &intruder_email, &password, "Laptop").await;
+        let owner_session_id = list(&ctx, &owner.access_token).await[0]["id"].as_str().expect("Expected id").to_string();
+
+        let response = send(&ctx, build_revoke_request(&owner_session_id, &intruder.access_token)).await;
+        let unknown = send(&ctx, build_revoke_request(&Uuid::now_v7().to_string(), &intruder.access_token)).await;
+
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        assert_eq!(StatusCode::NOT_FOUND, unknown.status());
+        assert_eq!(1, list(&ctx, &owner.access_token).await.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use rstest::rstest;
+    use serde_json::json;
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+    use crate::commons::{AuthCommons, TestContext};
+
+    fn build_request(email: &str, password: &str, client_ip: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .header("X-Forwarded-For", client_ip)
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_unlock_request(customer_id: &Uuid, admin_token: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(format!("/admin/customers/{}/unlock", customer_id))
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    async fn sing_in(ctx: &&mut TestContext, email: &str, password: &str, client_ip: &str) -> Response {
+        ctx.app.clone().oneshot(build_request(email, password, client_ip)).await.expect("Failed to sing in")
+    }
+
+    async fn fail_sing_in(ctx: &&mut TestContext, email: &str, times: usize) {
+        for _ in 0..times {
+            let response = sing_in(ctx, email, "wr0ng$ecreT", "10.0.0.1").await;
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_lock_account_after_max_failures(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        fail_sing_in(&ctx, &email, 5).await;
+
+        let response = sing_in(&ctx, &email, &password, "10.0.0.1").await;
+
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        let retry_after: i64 = response.headers().get("Retry-After").expect("Expected Retry-After").to_str()?.parse()?;
+        assert!(retry_after > 0 && retry_after <= 31);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_reset_failures_when_sing_in_succeeds(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        fail_sing_in(&ctx, &email, 4).await;
+        assert_eq!(StatusCode::OK, sing_in(&ctx, &email, &password, "10.0.0.1").await.status());
+
+        fail_sing_in(&ctx, &email, 4).await;
+
+        assert_eq!(StatusCode::OK, sing_in(&ctx, &email, &password, "10.0.0.1").await.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_lock_client_ip_after_max_failures(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        env::set_var("TRUST_FORWARDED_FOR", "true");
+        env::set_var("SIGN_IN_MAX_FAILURES_PER_IP", "3");
+        for attempt in 0..3 {
+            sing_in(&ctx, &format!("unknown{}@fiap.com.br", attempt), &password, "10.0.0.2").await;
+        }
+
+        let same_ip = sing_in(&ctx, &email, &password, "10.0.0.2").await;
+        let other_ip = sing_in(&ctx, &email, &password, "10.0.0.3").await;
+
+        env::remove_var("TRUST_FORWARDED_FOR");
+        env::remove_var("SIGN_IN_MAX_FAILURES_PER_IP");
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, same_ip.status());
+        assert_eq!(StatusCode::OK, other_ip.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_allow_sing_in_when_admin_unlocks_account(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        fail_sing_in(&ctx, &email, 5).await;
+        let admin_token = AuthCommons::admin_access_token(&ctx).await;
+
+        let forbidden = ctx.app.clone().oneshot(build_unlock_request(&customer.id, "wrong-token")).await?;
+        let response = ctx.app.clone().oneshot(build_unlock_request(&customer.id, &admin_token)).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, forbidden.status());
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(StatusCode::OK, sing_in(&ctx, &email, &password, "10.0.0.1").await.status());
+        Ok(())
+    }
+}
@@ -4,6 +4,10 @@ mod commons;
 mod test {
     use axum::body::Body;
     use axum::http::{Method, Request, StatusCode};
+    use http_body_util::BodyExt;
+    use login_auth_service::feature::auth::domain::{Customer, CustomerStatus};
+    use login_auth_service::support::hash::hash_bcrypt::HashBCrypt;
+    use login_auth_service::support::hash::hash_password::PasswordHasher;
     use rstest::rstest;
     use serde_json::{json, Value};
     use serial_test::serial;
@@ -44,6 +48,7 @@ mod test {
         let body_json = body_as_json_value(body).await;
         assert_eq!(StatusCode::OK, parts.status);
         assert!(parts.headers.get("Authorization").is_some());
+        assert!(parts.headers.get("Refresh-Token").is_some());
         assert_eq!(email, body_json.get("email").expect("Failed to read email").as_str().expect("failed to parse str"));
         Ok(())
     }
@@ -99,4 +104,56 @@ mod test {
         assert_eq!(StatusCode::UNAUTHORIZED, response.status());
         Ok(())
     }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_same_response_when_sing_in_with_unknown_email_or_wrong_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let wrong_password_request = build_request(build_request_body(&email, &String::from("wrongPassword")));
+        let unknown_email_request = build_request(build_request_body(&String::from("unknown@fiap.com.br"), &password));
+
+        let wrong_password = ctx.app.clone().oneshot(wrong_password_request).await?;
+        let unknown_email = ctx.app.clone().oneshot(unknown_email_request).await?;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, wrong_password.status());
+        assert_eq!(wrong_password.status(), unknown_email.status());
+        assert_eq!(wrong_password.headers(), unknown_email.headers());
+        let wrong_password_body = wrong_password.into_body().collect().await?.to_bytes();
+        let unknown_email_body = unknown_email.into_body().collect().await?.to_bytes();
+        assert_eq!(wrong_password_body, unknown_email_body);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_rehash_bcrypt_password_with_argon2id_when_sing_in(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let password_hashed = HashBCrypt.encode(&password).expect("Failed to hash with bcrypt");
+        let mut customer = Customer::new(email.clone(), password_hashed);
+        customer.status = CustomerStatus::Active;
+        let mut transaction = ctx.app_state.begin_transaction().await.expect("Failed to begin transaction");
+        Customer::insert(&mut transaction, customer).await.expect("Failed to insert customer");
+        ctx.app_state.commit_transaction(transaction).await.expect("Failed to commit transaction");
+
+        let response = ctx.app.clone().oneshot(build_request(build_request_body(&email, &password))).await?;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let mut transaction = ctx.app_state.begin_transaction().await.expect("Failed to begin transaction");
+        let stored_customer = Customer::get_by(&mut transaction, email.clone()).await.expect("Failed to get customer");
+        assert!(stored_customer.password.starts_with("$argon2id$"));
+        let second_response = ctx.app.clone().oneshot(build_request(build_request_body(&email, &password))).await?;
+        assert_eq!(StatusCode::OK, second_response.status());
+        Ok(())
+    }
 }
@@ -9,7 +9,7 @@ mod test {
     use serial_test::serial;
     use test_context::test_context;
     use tower::ServiceExt;
-    use crate::commons::{AuthCommons, TestContext};
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
 
     fn build_request(body: Value) -> Request<Body> {
         Request::builder()
@@ -96,4 +96,79 @@ mod test {
         assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
         Ok(())
     }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_errors_by_field_when_sing_up_with_invalid_email_and_weak_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("a");
+        let password = String::from("1");
+        let request = build_request(build_request_body(&email, &password));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let json = body_as_json_value(response.into_body()).await;
+        assert_eq!(json["errors"]["email"], json!(["email is not a valid address"]));
+        assert_eq!(
+            json["errors"]["password"],
+            json!([
+                "password must have at least 8 characters",
+                "password must have a lowercase letter",
+                "password must have an uppercase letter"
+            ])
+        );
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_sing_up_with_password_longer_than_bcrypt_limit(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = format!("my$ecr3T{}", "a".repeat(65));
+        let request = build_request(build_request_body(&email, &password));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let json = body_as_json_value(response.into_body()).await;
+        assert_eq!(json["errors"]["password"], json!(["password must have at most 64 bytes"]));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_normalize_email_when_sing_up(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("  User@FIAP.com.br ");
+        let password = String::from("my$ecr3T");
+        let request = build_request(build_request_body(&email, &password));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let json = body_as_json_value(response.into_body()).await;
+        assert_eq!(json["email"], json!("user@fiap.com.br"));
+        let message = ctx.mail_sender.last_message_to("user@fiap.com.br").expect("Expected a verification mail");
+        let token = message.body.split("?token=").nth(1).expect("Expected a token in the mail").trim();
+        let verify_request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/verify-email"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "token": token }).to_string()))?;
+        ctx.app.clone().oneshot(verify_request).await?;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        assert!(!tokens.access_token.is_empty());
+        Ok(())
+    }
 }
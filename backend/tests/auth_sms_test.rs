@@ -0,0 +1,241 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use login_auth_service::support::sms::SmsMessage;
+    use crate::commons::{AuthCommons, TestContext};
+
+    const PHONE_NUMBER: &str = "+5511987654321";
+
+    fn build_request(method: Method, uri: &str, body: Value, jwt: Option<&String>) -> Request<Body> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json");
+        let builder = match jwt {
+            Some(jwt) => builder.header("Authorization", format!("Bearer {}", jwt)),
+            None => builder,
+        };
+        builder
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn signed_in_customer(ctx: &&mut TestContext) -> String {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(ctx, &email, &password).await;
+        AuthCommons::sing_in(ctx, &email, &password).await.access_token
+    }
+
+    fn change_phone_request(phone_number: &str, current_password: &str, jwt: &String) -> Request<Body> {
+        let body = json!({ "phoneNumber": phone_number, "currentPassword": current_password });
+        build_request(Method::PUT, "/auth/phone", body, Some(jwt))
+    }
+
+    fn confirm_phone_request(code: &str, jwt: &String) -> Request<Body> {
+        build_request(Method::POST, "/auth/phone/confirm", json!({ "code": code }), Some(jwt))
+    }
+
+    async fn customer_with_phone_number(ctx: &&mut TestContext) {
+        let access_token = signed_in_customer(ctx).await;
+        let response = send(ctx, change_phone_request("+55 (11) 98765-4321", "my$ecr3T", &access_token)).await;
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        let code = confirmation_code(ctx).expect("Expected a confirmation sms");
+        let response = send(ctx, confirm_phone_request(&code, &access_token)).await;
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+    }
+
+    fn digits(message: &SmsMessage) -> String {
+        message.body.chars().filter(|c| c.is_ascii_digit()).collect()
+    }
+
+    fn sign_in_messages(ctx: &&mut TestContext) -> Vec<SmsMessage> {
+        ctx.sms_sender
+            .messages()
+            .into_iter()
+            .filter(|message| message.to == PHONE_NUMBER && message.body.starts_with("Your sign in code"))
+            .collect()
+    }
+
+    fn confirmation_code(ctx: &&mut TestContext) -> Option<String> {
+        ctx.sms_sender
+            .last_message_to(PHONE_NUMBER)
+            .filter(|message| message.body.starts_with("Your code to confirm"))
+            .map(|message| digits(&message))
+    }
+
+    /// The code is texted in the background, after the request response.
+    async fn sms_code(ctx: &&mut TestContext) -> String {
+        for _ in 0..50 {
+            if let Some(message) = sign_in_messages(ctx).last() {
+                return digits(message);
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Expected a sms to {}", PHONE_NUMBER);
+    }
+
+    async fn verify(ctx: &&mut TestContext, code: &str) -> Response {
+        send(ctx, build_request(Method::POST, "/auth/sms/verify", json!({ "phoneNumber": PHONE_NUMBER, "code": code }), None)).await
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_sing_in_with_sms_code_only_once(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        customer_with_phone_number(&ctx).await;
+        let request = send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        let code = sms_code(&ctx).await;
+
+        let response = verify(&ctx, &code).await;
+
+        assert_eq!(StatusCode::ACCEPTED, request.status());
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("Authorization").is_some());
+        assert!(response.headers().get("Refresh-Token").is_some());
+        assert_eq!(StatusCode::UNAUTHORIZED, verify(&ctx, &code).await.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_sms_code_exceeds_max_attempts(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        customer_with_phone_number(&ctx).await;
+        send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        let code = sms_code(&ctx).await;
+        let wrong_code = if code == "000000" { "111111" } else { "000000" };
+        for _ in 0..5 {
+            assert_eq!(StatusCode::UNAUTHORIZED, verify(&ctx, wrong_code).await.status());
+        }
+
+        let response = verify(&ctx, &code).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_accept_sms_request_for_unknown_phone_number_without_sms(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(ctx.sms_sender.messages().is_empty());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_text_another_code_before_resend_interval(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        customer_with_phone_number(&ctx).await;
+        send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        sms_code(&ctx).await;
+
+        let response = send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert_eq!(1, sign_in_messages(&ctx).len());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_phone_number_is_not_e164(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let access_token = signed_in_customer(&ctx).await;
+
+        let response = send(&ctx, change_phone_request("11 98765-4321", "my$ecr3T", &access_token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_changing_phone_number_with_wrong_current_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let access_token = signed_in_customer(&ctx).await;
+
+        let response = send(&ctx, change_phone_request(PHONE_NUMBER, "wr0ng$ecreT", &access_token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert!(ctx.sms_sender.messages().is_empty());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_phone_number_unset_until_code_is_confirmed(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let access_token = signed_in_customer(&ctx).await;
+        send(&ctx, change_phone_request(PHONE_NUMBER, "my$ecr3T", &access_token)).await;
+        let code = confirmation_code(&ctx).expect("Expected a confirmation sms");
+        let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+        let confirm = send(&ctx, confirm_phone_request(wrong_code, &access_token)).await;
+        send(&ctx, build_request(Method::POST, "/auth/sms/request", json!({ "phoneNumber": PHONE_NUMBER }), None)).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, confirm.status());
+        assert!(sign_in_messages(&ctx).is_empty());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_text_another_confirmation_code_before_resend_interval(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let access_token = signed_in_customer(&ctx).await;
+        send(&ctx, change_phone_request(PHONE_NUMBER, "my$ecr3T", &access_token)).await;
+
+        let response = send(&ctx, change_phone_request(PHONE_NUMBER, "my$ecr3T", &access_token)).await;
+
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!(1, ctx.sms_sender.messages().len());
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{AuthCommons, TestContext};
+
+    fn build_request(uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn sing_up(ctx: &&mut TestContext, email: &String, password: &String) {
+        let request = build_request("/auth/singup", json!({ "email": email, "password": password }));
+        let response = ctx.app.clone().oneshot(request).await.expect("Failed to sing up");
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    fn verification_token(ctx: &&mut TestContext, email: &str) -> String {
+        let message = ctx.mail_sender.last_message_to(email).expect("Expected a verification mail");
+        message.body.split("?token=").nth(1).expect("Expected a token in the mail").trim().to_string()
+    }
+
+    async fn sing_in_status(ctx: &&mut TestContext, email: &String, password: &String) -> StatusCode {
+        let request = build_request("/auth/singin", json!({ "email": email, "password": password }));
+        ctx.app.clone().oneshot(request).await.expect("Failed to sing in").status()
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_allow_sing_in_when_email_is_verified(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        sing_up(&ctx, &email, &password).await;
+        let token = verification_token(&ctx, &email);
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/verify-email", json!({ "token": token }))).await?;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_sing_in_with_email_not_verified(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        sing_up(&ctx, &email, &password).await;
+
+        let status = sing_in_status(&ctx, &email, &password).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, status);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_allow_sing_in_with_email_not_verified_during_grace_period(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        sing_up(&ctx, &email, &password).await;
+        env::set_var("EMAIL_VERIFICATION_GRACE_PERIOD_SECONDS", "3600");
+
+        let status = sing_in_status(&ctx, &email, &password).await;
+
+        env::remove_var("EMAIL_VERIFICATION_GRACE_PERIOD_SECONDS");
+        assert_eq!(StatusCode::OK, status);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_verification_token_is_used_twice(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        sing_up(&ctx, &email, &password).await;
+        let token = verification_token(&ctx, &email);
+        ctx.app.clone().oneshot(build_request("/auth/verify-email", json!({ "token": token }))).await?;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/verify-email", json!({ "token": token }))).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_verification_token_is_invalid(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = ctx.app.clone().oneshot(build_request("/auth/verify-email", json!({ "token": "invalid" }))).await?;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_send_new_token_when_resend_verification(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        sing_up(&ctx, &email, &password).await;
+        let first_token = verification_token(&ctx, &email);
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/verify-email/resend", json!({ "email": email }))).await?;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        let second_token = verification_token(&ctx, &email);
+        assert_ne!(first_token, second_token);
+        let verified = ctx.app.clone().oneshot(build_request("/auth/verify-email", json!({ "token": second_token }))).await?;
+        assert_eq!(StatusCode::NO_CONTENT, verified.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_send_mail_when_resend_verification_for_verified_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+
+        let response = ctx.app.clone().oneshot(build_request("/auth/verify-email/resend", json!({ "email": email }))).await?;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(ctx.mail_sender.messages().is_empty());
+        Ok(())
+    }
+}
@@ -12,7 +12,17 @@ mod test {
     use uuid::Uuid;
     use crate::commons::{AuthCommons, BiometricsCommons, TestContext};
 
-    fn build_request(body: Value) -> Request<Body> {
+    fn build_request(body: Value, jwt: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/biometrics/actions/create"))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn build_request_without_authorization(body: Value) -> Request<Body> {
         Request::builder()
             .method(Method::POST)
             .uri(String::from("/biometrics/actions/create"))
@@ -23,14 +33,14 @@ mod test {
 
     fn build_request_body(customer_id: &Uuid, image_path: &String) -> Value {
         json!({
-            "customer_id": customer_id,
-            "image_path": image_path
+            "customerId": customer_id,
+            "imagePath": image_path
         })
     }
     fn build_request_body_customer_id_empty(image_path: &String) -> Value {
         json!({
-            "customer_id": "",
-            "image_path": image_path
+            "customerId": "",
+            "imagePath": image_path
         })
     }
 
@@ -42,7 +52,8 @@ mod test {
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
-        let request = build_request(build_request_body(&customer.id, &String::from("s3//image")));
+        let jwt = AuthCommons::generate_jwt(&customer);
+        let request = build_request(build_request_body(&customer.id, &format!("{}/selfie.png", customer.id)), &jwt);
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::OK, response.status());
@@ -57,7 +68,9 @@ mod test {
     async fn should_return_error_when_create_biometric_with_customer_id_is_empty(
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let request = build_request(build_request_body_customer_id_empty(&String::from("s3//image")));
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let jwt = AuthCommons::generate_jwt(&customer);
+        let request = build_request(build_request_body_customer_id_empty(&String::from("s3//image")), &jwt);
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
@@ -72,7 +85,8 @@ mod test {
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
-        let request = build_request(build_request_body(&customer.id, &String::from("")));
+        let jwt = AuthCommons::generate_jwt(&customer);
+        let request = build_request(build_request_body(&customer.id, &String::from("")), &jwt);
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::BAD_REQUEST, response.status());
@@ -83,13 +97,45 @@ mod test {
     #[rstest]
     #[tokio::test]
     #[serial]
-    async fn should_return_error_when_create_biometric_with_customer_id_invalid(
+    async fn should_return_error_when_create_biometric_for_another_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let jwt = AuthCommons::generate_jwt(&customer);
+        let request = build_request(build_request_body(&Uuid::now_v7(), &String::from("s3://image")), &jwt);
+
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_create_biometric_without_authorization(
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let request = build_request(build_request_body(&Uuid::now_v7(), &String::from("s3://image")));
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let request = build_request_without_authorization(build_request_body(&customer.id, &String::from("s3://image")));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_create_biometric_with_invalid_jwt(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let request = build_request(build_request_body(&customer.id, &String::from("s3://image")), &String::from("invalid.jwt.token"));
 
         let response = ctx.app.clone().oneshot(request).await?;
-        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
         Ok(())
     }
 }
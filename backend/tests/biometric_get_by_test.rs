@@ -9,13 +9,16 @@ mod test {
     use test_context::test_context;
     use tower::ServiceExt;
     use uuid::Uuid;
-    use crate::commons::{BiometricsCommons, TestContext};
+    use login_auth_service::feature::auth::domain::Role;
+    use login_auth_service::support::jwt::{AuthorizationClaims, Jwt};
+    use crate::commons::{AuthCommons, BiometricsCommons, TestContext};
 
-    fn build_request(url: String) -> Request<Body> {
+    fn build_request(url: String, jwt: &String) -> Request<Body> {
         Request::builder()
             .method(Method::GET)
             .uri(String::from(format!("/biometrics/actions/get/{}", url)))
             .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt))
             .body(Body::empty())
             .expect("Failed to build request")
     }
@@ -28,7 +31,7 @@ mod test {
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
-        let request = build_request(customer.id.to_string());
+        let request = build_request(customer.id.to_string(), &AuthCommons::generate_jwt(&customer));
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::OK, response.status());
@@ -43,7 +46,8 @@ mod test {
     async fn should_return_error_when_customer_id_is_empty(
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let request = build_request(Uuid::nil().to_string());
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let request = build_request(Uuid::nil().to_string(), &AuthCommons::generate_jwt(&customer));
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::BAD_REQUEST, response.status());
@@ -54,13 +58,47 @@ mod test {
     #[rstest]
     #[tokio::test]
     #[serial]
-    async fn should_return_error_when_customer_id_invalid(
+    async fn should_return_error_when_customer_has_no_biometric(
         ctx: &mut TestContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let request = build_request(Uuid::now_v7().to_string());
+        let customer = AuthCommons::craete_customer(&ctx, &String::from("user@fiap.com.br"), &String::from("pass")).await;
+        let request = build_request(customer.id.to_string(), &AuthCommons::generate_jwt(&customer));
 
         let response = ctx.app.clone().oneshot(request).await?;
         assert_eq!(StatusCode::NOT_FOUND, response.status());
         Ok(())
     }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_get_biometric_of_another_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (owner, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let intruder = AuthCommons::craete_customer(&ctx, &String::from("intruder@fiap.com.br"), &String::from("pass")).await;
+        let request = build_request(owner.id.to_string(), &AuthCommons::generate_jwt(&intruder));
+
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_jwt_is_expired(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let claims = AuthorizationClaims::new(customer.id, Uuid::now_v7(), customer.email.clone(), vec![Role::Customer], -120);
+        let jwt = AuthorizationClaims::generate_jwt(&claims, &ctx.app_state.jwt_key_ring).expect("Failed to generate jwt");
+        let request = build_request(customer.id.to_string(), &jwt);
+
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
 }
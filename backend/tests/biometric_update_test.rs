@@ -0,0 +1,195 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use login_auth_service::feature::auth::domain::Role;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+    use crate::commons::{body_as_json_value, AuthCommons, BiometricsCommons, TestContext};
+
+    fn build_request(customer_id: &Uuid, image_path: &str, status: &str, jwt: &String) -> Request<Body> {
+        let body = json!({ "customer_id": customer_id, "image_path": image_path, "status": status });
+        Request::builder()
+            .method(Method::PUT)
+            .uri(String::from("/biometrics/actions/update"))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn status_of(response: Response) -> Value {
+        body_as_json_value(response.into_body()).await["status"].clone()
+    }
+
+    async fn reviewer_jwt(ctx: &&mut TestContext) -> String {
+        let email = String::from("reviewer@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let reviewer = AuthCommons::craete_customer(ctx, &email, &password).await;
+        AuthCommons::grant_roles(ctx, &reviewer, &[Role::Customer, Role::Reviewer]).await;
+        AuthCommons::sing_in(ctx, &email, &password).await.access_token
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_change_status_of_any_customer_when_reviewer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let jwt = reviewer_jwt(&ctx).await;
+
+        let take_again = send(&ctx, build_request(&customer.id, "s3://image", "take_again", &jwt)).await;
+        let conclued = send(&ctx, build_request(&customer.id, "s3://image", "conclued", &jwt)).await;
+
+        assert_eq!(StatusCode::OK, take_again.status());
+        assert_eq!(json!("take_again"), status_of(take_again).await);
+        assert_eq!(StatusCode::OK, conclued.status());
+        assert_eq!(json!("conclued"), status_of(conclued).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_forbidden_when_reviewer_replaces_image_of_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let jwt = reviewer_jwt(&ctx).await;
+
+        let response = send(&ctx, build_request(&customer.id, "s3://other", "in_analysis", &jwt)).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_forbidden_when_reviewer_changes_status_of_own_biometric(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("reviewer@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let (reviewer, _) = BiometricsCommons::craete_biometrics(&ctx, &email, &password, &String::from("s3://image")).await;
+        AuthCommons::grant_roles(&ctx, &reviewer, &[Role::Customer, Role::Reviewer]).await;
+        let jwt = AuthCommons::sing_in(&ctx, &email, &password).await.access_token;
+
+        let response = send(&ctx, build_request(&reviewer.id, "s3://image", "conclued", &jwt)).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["message"], json!("reviewers are not allowed to change the status of their own biometric"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_forbidden_when_customer_changes_status(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+
+        let response = send(&ctx, build_request(&customer.id, "s3://image", "conclued", &AuthCommons::generate_jwt(&customer))).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["message"], json!("only reviewers are allowed to change the biometric status"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_send_biometric_back_to_analysis_when_customer_replaces_image(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let reviewer = reviewer_jwt(&ctx).await;
+        send(&ctx, build_request(&customer.id, "s3://image", "reproved", &reviewer)).await;
+        let new_image = format!("{}/new-selfie.png", customer.id);
+
+        let response = send(&ctx, build_request(&customer.id, &new_image, "reproved", &AuthCommons::generate_jwt(&customer))).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["imagePath"], json!(new_image));
+        assert_eq!(body["status"], json!("in_analysis"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_customer_points_image_to_another_folder(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let (other, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("other@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let jwt = AuthCommons::generate_jwt(&customer);
+
+        let foreign = send(&ctx, build_request(&customer.id, &format!("{}/selfie.png", other.id), "in_analysis", &jwt)).await;
+        let escaping = send(&ctx, build_request(&customer.id, &format!("{}/../{}/selfie.png", customer.id, other.id), "in_analysis", &jwt)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, foreign.status());
+        assert_eq!(StatusCode::BAD_REQUEST, escaping.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_forbidden_when_customer_updates_biometric_of_another_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (owner, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("owner@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let intruder = AuthCommons::craete_customer(&ctx, &String::from("intruder@fiap.com.br"), &String::from("pass")).await;
+
+        let response = send(&ctx, build_request(&owner.id, "s3://other", "in_analysis", &AuthCommons::generate_jwt(&intruder))).await;
+
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_biometric_of_any_customer_when_reviewer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &String::from("user@fiap.com.br"), &String::from("pass"), &String::from("s3://image")).await;
+        let jwt = reviewer_jwt(&ctx).await;
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/get/{}", customer.id))
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(Body::empty())
+            .expect("Failed to build request");
+
+        let response = send(&ctx, request).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        Ok(())
+    }
+}
@@ -2,13 +2,21 @@ use std::env;
 use std::net::{SocketAddr, TcpListener};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use axum::body::Body;
+use axum::http::{Method, Request};
 use axum::response::Response;
 use axum::{async_trait, Router};
 use chrono::SecondsFormat;
-use login_auth_service::feature::auth::domain::Customer;
+use login_auth_service::feature::auth::domain::{Customer, CustomerRole, CustomerStatus, Role};
 use login_auth_service::feature::biometrics::domain::Biometrics;
+use login_auth_service::support::hash::hash_password::HashPassword;
+use login_auth_service::support::jwt::{AuthorizationClaims, Jwt};
+use login_auth_service::support::jwt_keys::JwtKeyRing;
+use login_auth_service::support::mail::memory::InMemoryMailSender;
+use login_auth_service::support::sms::memory::InMemorySmsSender;
+use login_auth_service::support::storage::memory::InMemoryImageStorage;
 use pg_embed::pg_enums::PgAuthMethod;
 use pg_embed::pg_fetch::{PgFetchSettings, PG_V15};
 use pg_embed::postgres::{PgEmbed, PgSettings};
@@ -16,6 +24,7 @@ use rand::Rng;
 use serde_json::{json, Value};
 use sqlx::migrate::MigrateError;
 use test_context::AsyncTestContext;
+use tower::ServiceExt;
 use uuid::Uuid;
 use wiremock::MockServer;
 
@@ -26,6 +35,12 @@ use http_body_util::BodyExt;
 pub struct TestContext {
     pub database: Option<PgEmbed>,
     pub mock_server: MockServer,
+    #[allow(dead_code)]
+    pub mail_sender: InMemoryMailSender,
+    #[allow(dead_code)]
+    pub sms_sender: InMemorySmsSender,
+    #[allow(dead_code)]
+    pub image_storage: InMemoryImageStorage,
     pub app_state: AppState,
     pub app: Router,
 }
@@ -88,9 +103,15 @@ impl AsyncTestContext for TestContext {
     async fn setup() -> TestContext {
         let database = DatabaseConfigTest::embed_postgres().await;
         let mock_server = create_mock_server().await;
-        let app_state = AppState::create()
+        let mail_sender = InMemoryMailSender::default();
+        let sms_sender = InMemorySmsSender::default();
+        let image_storage = InMemoryImageStorage::default();
+        let mut app_state = AppState::create()
             .await
             .expect("Failed to create app state");
+        app_state.mail_sender = Arc::new(mail_sender.clone());
+        app_state.sms_sender = Arc::new(sms_sender.clone());
+        app_state.image_storage = Arc::new(image_storage.clone());
         let app = AppRoutes::routes(app_state.clone())
             .await
             .expect("Failed to create app");
@@ -102,6 +123,9 @@ impl AsyncTestContext for TestContext {
         TestContext {
             database,
             mock_server,
+            mail_sender,
+            sms_sender,
+            image_storage,
             app_state,
             app,
         }
@@ -192,6 +216,12 @@ pub async fn body_as_json_value(body: Body) -> Value {
     serde_json::from_slice(&bytes).expect("Failed to serialize json")
 }
 
+#[allow(dead_code)]
+pub struct SingInTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
 #[allow(dead_code)]
 pub struct AuthCommons;
 impl AuthCommons {
@@ -206,7 +236,10 @@ impl AuthCommons {
         .await
         .expect("Failed to create transaction");
 
-        let mut customer = Customer::new(email.clone(), password.clone()).expect("Failed to new Customer");
+        let password_hashed = HashPassword::encode(password).expect("Failed to hash password");
+        let mut customer = Customer::new(email.clone(), password_hashed);
+        customer.status = CustomerStatus::Active;
+        customer.email_verified_at = Some(customer.created_at);
 
         customer = Customer::insert(&mut transaction, customer)
             .await
@@ -219,6 +252,79 @@ impl AuthCommons {
         customer
     }
 
+    #[allow(dead_code)]
+    pub async fn grant_roles(ctx: &&mut TestContext, customer: &Customer, roles: &[Role]) {
+        let mut transaction = ctx
+            .app_state
+            .begin_transaction()
+            .await
+            .expect("Failed to create transaction");
+        CustomerRole::replace_all(&mut transaction, customer.id, roles)
+            .await
+            .expect("Failed to grant roles");
+        ctx.app_state
+            .commit_transaction(transaction)
+            .await
+            .expect("Failed to commit transaction");
+    }
+
+    /// Signs in an account holding the admin role, created on first use.
+    #[allow(dead_code)]
+    pub async fn admin_access_token(ctx: &&mut TestContext) -> String {
+        let email = String::from("operator@fiap.com.br");
+        let password = String::from("0perat0r$ecreT");
+        let mut transaction = ctx
+            .app_state
+            .begin_transaction()
+            .await
+            .expect("Failed to create transaction");
+        let admin = Customer::find_by(&mut transaction, email.clone())
+            .await
+            .expect("Failed to find admin");
+        ctx.app_state
+            .commit_transaction(transaction)
+            .await
+            .expect("Failed to commit transaction");
+        if admin.is_none() {
+            let admin = Self::craete_customer(ctx, &email, &password).await;
+            Self::grant_roles(ctx, &admin, &[Role::Admin]).await;
+        }
+        Self::sing_in(ctx, &email, &password).await.access_token
+    }
+
+    #[allow(dead_code)]
+    pub fn generate_jwt(customer: &Customer) -> String {
+        let claims = AuthorizationClaims::new(customer.id, Uuid::now_v7(), customer.email.clone(), vec![Role::Customer], 30);
+        AuthorizationClaims::generate_jwt(&claims, &JwtKeyRing::from_env().expect("Failed to load key ring"))
+            .expect("Failed to generate jwt")
+    }
+
+    #[allow(dead_code)]
+    pub async fn sing_in(
+        ctx: &&mut TestContext,
+        email: &String,
+        password: &String
+    ) -> SingInTokens {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request");
+        let response = ctx.app.clone().oneshot(request).await.expect("Failed to sing in");
+        let header = |name: &str| response
+            .headers()
+            .get(name)
+            .unwrap_or_else(|| panic!("Expected header '{}'", name))
+            .to_str()
+            .expect("Failed to parse header")
+            .to_string();
+        SingInTokens {
+            access_token: header("Authorization"),
+            refresh_token: header("Refresh-Token"),
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn valid_customer_inserted(
         ctx: &&mut TestContext,
@@ -308,18 +414,18 @@ impl BiometricsCommons {
             .to_bytes();
         let json_value: Value = serde_json::from_slice(&bytes).expect("Failed to serialize json");
       
-        assert!(json_value.get("customer_id").is_some());
-        assert!(json_value.get("image_path").is_some());
+        assert!(json_value.get("customerId").is_some());
+        assert!(json_value.get("imagePath").is_some());
         assert!(json_value.get("status").is_some());
-        assert!(json_value.get("created_at").is_some());
-        assert!(json_value.get("updated_at").is_some());
+        assert!(json_value.get("createdAt").is_some());
+        assert!(json_value.get("updatedAt").is_some());
 
-        let customer_id = json_value.get("customer_id")
+        let customer_id = json_value.get("customerId")
             .expect("Failed to read id")
             .as_str()
             .expect("Failed to parse str")
             .to_string();
-        let image_path = json_value.get("image_path")
+        let image_path = json_value.get("imagePath")
             .expect("Failed to read id")
             .as_str()
             .expect("Failed to parse str")
@@ -329,12 +435,12 @@ impl BiometricsCommons {
             .as_str()
             .expect("Failed to parse str")
             .to_string();
-        let created_at = json_value.get("created_at")
+        let created_at = json_value.get("createdAt")
             .expect("Failed to read id")
             .as_str()
             .expect("Failed to parse str")
             .to_string();
-        let updated_at = json_value.get("updated_at")
+        let updated_at = json_value.get("updatedAt")
             .expect("Failed to read id")
             .as_str()
             .expect("Failed to parse str")
@@ -0,0 +1,192 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use login_auth_service::feature::auth::domain::{Customer, CustomerStatus};
+    use login_auth_service::feature::customers::deletion::DeletionWorker;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, BiometricsCommons, TestContext};
+
+    fn build_request(method: Method, uri: &str, body: Option<Value>, jwt: &String) -> Request<Body> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt));
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        builder.body(body).expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn sing_in_status(ctx: &&mut TestContext, email: &String, password: &String) -> StatusCode {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request");
+        send(ctx, request).await.status()
+    }
+
+    async fn end_grace_period(ctx: &&mut TestContext) {
+        sqlx::query("UPDATE customer_deletion SET scheduled_for = now()")
+            .execute(&ctx.app_state.postgres_pool)
+            .await
+            .expect("Failed to end grace period");
+    }
+
+    async fn process_due(ctx: &&mut TestContext) -> usize {
+        DeletionWorker::process_due(&ctx.app_state).await.expect("Failed to process deletions")
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_erase_customer_only_after_grace_period(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let image_path = format!("{}/selfie.png", customer.id);
+        let biometric = json!({ "customerId": customer.id, "imagePath": image_path });
+        assert_eq!(StatusCode::OK, send(&ctx, build_request(Method::POST, "/biometrics/actions/create", Some(biometric), &tokens.access_token)).await.status());
+
+        let response = send(&ctx, build_request(Method::DELETE, "/customers/me", Some(json!({ "currentPassword": password })), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(body_as_json_value(response.into_body()).await["scheduledFor"].is_string());
+        assert!(ctx.mail_sender.last_message_to(&email).is_some());
+        assert_eq!(0, process_due(&ctx).await);
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+
+        end_grace_period(&ctx).await;
+        assert_eq!(1, process_due(&ctx).await);
+
+        assert_eq!(vec![image_path], ctx.image_storage.deleted());
+        let biometrics: i64 = sqlx::query_scalar("SELECT count(*) FROM biometrics WHERE customer_id = $1")
+            .bind(customer.id)
+            .fetch_one(&ctx.app_state.postgres_pool)
+            .await?;
+        assert_eq!(0, biometrics);
+        let mut transaction = ctx.app_state.begin_transaction().await.expect("Failed to create transaction");
+        let deleted = Customer::get_by_id(&mut transaction, customer.id).await.expect("Failed to get customer");
+        ctx.app_state.commit_transaction(transaction).await.expect("Failed to commit transaction");
+        assert_eq!(CustomerStatus::Deleted, deleted.status);
+        assert_eq!(format!("deleted-{}@deleted.invalid", customer.id), deleted.email);
+        assert!(deleted.deleted_at.is_some());
+        assert_eq!(StatusCode::UNAUTHORIZED, sing_in_status(&ctx, &email, &password).await);
+        let me = send(&ctx, build_request(Method::GET, "/customers/me", None, &tokens.access_token)).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, me.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_deletion_record_and_not_run_it_twice(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        send(&ctx, build_request(Method::DELETE, "/customers/me", Some(json!({ "currentPassword": password })), &tokens.access_token)).await;
+        end_grace_period(&ctx).await;
+
+        assert_eq!(1, process_due(&ctx).await);
+        end_grace_period(&ctx).await;
+        assert_eq!(0, process_due(&ctx).await);
+
+        let (attempts, completed): (i32, bool) = sqlx::query_as("SELECT attempts, completed_at IS NOT NULL FROM customer_deletion WHERE customer_id = $1")
+            .bind(customer.id)
+            .fetch_one(&ctx.app_state.postgres_pool)
+            .await?;
+        assert_eq!(0, attempts);
+        assert!(completed);
+        let sessions: i64 = sqlx::query_scalar("SELECT count(*) FROM customer_session WHERE customer_id = $1")
+            .bind(customer.id)
+            .fetch_one(&ctx.app_state.postgres_pool)
+            .await?;
+        assert_eq!(0, sessions);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_erase_customer_when_deletion_is_cancelled(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        send(&ctx, build_request(Method::DELETE, "/customers/me", Some(json!({ "currentPassword": password })), &tokens.access_token)).await;
+
+        let cancelled = send(&ctx, build_request(Method::DELETE, "/customers/me/deletion", None, &tokens.access_token)).await;
+        let nothing_pending = send(&ctx, build_request(Method::DELETE, "/customers/me/deletion", None, &tokens.access_token)).await;
+        end_grace_period(&ctx).await;
+
+        assert_eq!(StatusCode::NO_CONTENT, cancelled.status());
+        assert_eq!(StatusCode::NOT_FOUND, nothing_pending.status());
+        assert_eq!(0, process_due(&ctx).await);
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_delete_me_with_wrong_current_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = send(&ctx, build_request(Method::DELETE, "/customers/me", Some(json!({ "currentPassword": "wr0ng$ecreT" })), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        end_grace_period(&ctx).await;
+        assert_eq!(0, process_due(&ctx).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_delete_image_outside_of_customer_folder(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let other = AuthCommons::craete_customer(&ctx, &String::from("other@fiap.com.br"), &password).await;
+        BiometricsCommons::craete_biometrics(&ctx, &email, &password, &format!("{}/selfie.png", other.id)).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        send(&ctx, build_request(Method::DELETE, "/customers/me", Some(json!({ "currentPassword": password })), &tokens.access_token)).await;
+        end_grace_period(&ctx).await;
+
+        assert_eq!(1, process_due(&ctx).await);
+
+        assert!(ctx.image_storage.deleted().is_empty());
+        Ok(())
+    }
+}
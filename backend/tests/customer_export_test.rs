@@ -0,0 +1,184 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, BiometricsCommons, TestContext};
+
+    fn build_request(method: Method, uri: &str, body: Option<Value>, jwt: &String) -> Request<Body> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt));
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        builder.body(body).expect("Failed to build request")
+    }
+
+    fn build_image_request(token: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/biometrics/actions/image?token={}", token))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn export(ctx: &&mut TestContext, jwt: &String) -> Value {
+        let response = send(ctx, build_request(Method::GET, "/customers/me/export", None, jwt)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        body_as_json_value(response.into_body()).await
+    }
+
+    fn token_in(image_url: &Value) -> String {
+        image_url.as_str().expect("Expected image url")
+            .split("?token=").nth(1).expect("Expected a token in the link")
+            .to_string()
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_export_everything_held_about_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let image_path = format!("{}/selfie.png", customer.id);
+        let biometric = json!({ "customerId": customer.id, "imagePath": image_path });
+        assert_eq!(StatusCode::OK, send(&ctx, build_request(Method::POST, "/biometrics/actions/create", Some(biometric), &tokens.access_token)).await.status());
+
+        let response = send(&ctx, build_request(Method::GET, "/customers/me/export", None, &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let disposition = response.headers().get("Content-Disposition").expect("Expected Content-Disposition").to_str()?;
+        assert_eq!(format!("attachment; filename=\"customer-{}.json\"", customer.id), disposition);
+        let body = body_as_json_value(response.into_body()).await;
+        assert!(body["exportedAt"].is_string());
+        assert_eq!(body["customer"]["id"], json!(customer.id));
+        assert_eq!(body["customer"]["email"], json!(email));
+        assert_eq!(body["customer"]["status"], json!("active"));
+        assert!(body["customer"].get("password").is_none());
+        assert_eq!(body["biometrics"][0]["imagePath"], json!(image_path));
+        assert_eq!(body["biometrics"][0]["statusHistory"][0]["status"], json!("in_analysis"));
+        assert!(body["biometrics"][0]["imageUrl"].as_str().expect("Expected image url").starts_with("http"));
+        assert_eq!(1, body["sessions"].as_array().expect("Expected sessions").len());
+        assert!(body["sessions"][0]["ipAddress"].is_string());
+        assert_eq!(body["consents"], json!([{ "purpose": "biometrics", "grantedAt": body["consents"][0]["grantedAt"], "revokedAt": null }]));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_download_image_with_signed_link_of_export(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let image_path = format!("{}/selfie.png", customer.id);
+        ctx.image_storage.put(&image_path, b"png".to_vec());
+        let biometric = json!({ "customerId": customer.id, "imagePath": image_path });
+        send(&ctx, build_request(Method::POST, "/biometrics/actions/create", Some(biometric), &tokens.access_token)).await;
+        let token = token_in(&export(&ctx, &tokens.access_token).await["biometrics"][0]["imageUrl"]);
+
+        let response = send(&ctx, build_image_request(&token)).await;
+        let tampered = send(&ctx, build_image_request(&format!("{}x", token))).await;
+        let access_token = send(&ctx, build_image_request(&tokens.access_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(b"png".to_vec(), response.into_body().collect().await?.to_bytes().to_vec());
+        assert_eq!(StatusCode::UNAUTHORIZED, tampered.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, access_token.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_not_found_when_image_link_outlives_biometric(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let image_path = format!("{}/selfie.png", customer.id);
+        ctx.image_storage.put(&image_path, b"png".to_vec());
+        let biometric = json!({ "customerId": customer.id, "imagePath": image_path });
+        send(&ctx, build_request(Method::POST, "/biometrics/actions/create", Some(biometric), &tokens.access_token)).await;
+        let token = token_in(&export(&ctx, &tokens.access_token).await["biometrics"][0]["imageUrl"]);
+        sqlx::query("DELETE FROM biometrics WHERE customer_id = $1")
+            .bind(customer.id)
+            .execute(&ctx.app_state.postgres_pool)
+            .await?;
+
+        let response = send(&ctx, build_image_request(&token)).await;
+
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_export_without_authorization(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(String::from("/customers/me/export"))
+            .body(Body::empty())
+            .expect("Failed to build request");
+
+        let response = send(&ctx, request).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_not_hand_out_image_of_another_customer(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let password = String::from("my$ecr3T");
+        let victim = AuthCommons::craete_customer(&ctx, &String::from("victim@fiap.com.br"), &password).await;
+        let victim_image = format!("{}/selfie.png", victim.id);
+        ctx.image_storage.put(&victim_image, b"png".to_vec());
+        let email = String::from("user@fiap.com.br");
+        let (customer, _) = BiometricsCommons::craete_biometrics(&ctx, &email, &password, &victim_image).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let biometric = json!({ "customerId": customer.id, "imagePath": victim_image });
+
+        let created = send(&ctx, build_request(Method::POST, "/biometrics/actions/create", Some(biometric), &tokens.access_token)).await;
+        let token = token_in(&export(&ctx, &tokens.access_token).await["biometrics"][0]["imageUrl"]);
+        let response = send(&ctx, build_image_request(&token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, created.status());
+        assert_eq!(json!(format!("image_path must be under {}/", customer.id)), body_as_json_value(created.into_body()).await["message"]);
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        Ok(())
+    }
+}
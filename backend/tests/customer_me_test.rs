@@ -0,0 +1,214 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    fn build_request(method: Method, uri: &str, body: Option<Value>, jwt: &String) -> Request<Body> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(String::from(uri))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", jwt));
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        builder.body(body).expect("Failed to build request")
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn sing_in_status(ctx: &&mut TestContext, email: &String, password: &String) -> StatusCode {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request");
+        send(ctx, request).await.status()
+    }
+
+    fn token_in(body: &str) -> String {
+        body.split("?token=").nth(1).expect("Expected a token in the mail")
+            .lines().next().expect("Expected a token line").trim().to_string()
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_profile_when_get_me(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let customer = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = send(&ctx, build_request(Method::GET, "/customers/me", None, &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["id"], json!(customer.id));
+        assert_eq!(body["email"], json!(email));
+        assert_eq!(body["emailVerified"], json!(true));
+        assert_eq!(body["name"], Value::Null);
+        assert!(body["createdAt"].is_string());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_update_name_and_track_update_when_patch_me(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+
+        let response = send(&ctx, build_request(Method::PATCH, "/customers/me", Some(json!({ "name": "  Ada Lovelace " })), &tokens.access_token)).await;
+        let too_long = send(&ctx, build_request(Method::PATCH, "/customers/me", Some(json!({ "name": "a".repeat(201) })), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["name"], json!("Ada Lovelace"));
+        assert!(body["updatedAt"].is_string());
+        assert_eq!(StatusCode::BAD_REQUEST, too_long.status());
+        let me = body_as_json_value(send(&ctx, build_request(Method::GET, "/customers/me", None, &tokens.access_token)).await.into_body()).await;
+        assert_eq!(me["name"], json!("Ada Lovelace"));
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_change_password_and_end_other_sessions(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let new_password = String::from("n3w$ecreT");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let phone = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let tablet = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let body = json!({ "currentPassword": password, "newPassword": new_password });
+
+        let response = send(&ctx, build_request(Method::POST, "/customers/me/password", Some(body), &phone.access_token)).await;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, sing_in_status(&ctx, &email, &password).await);
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &new_password).await);
+        let phone_me = send(&ctx, build_request(Method::GET, "/customers/me", None, &phone.access_token)).await;
+        assert_eq!(StatusCode::OK, phone_me.status());
+        let tablet_me = send(&ctx, build_request(Method::GET, "/customers/me", None, &tablet.access_token)).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, tablet_me.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_change_password_with_wrong_current_password(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let body = json!({ "currentPassword": "wr0ng$ecreT", "newPassword": "n3w$ecreT" });
+
+        let response = send(&ctx, build_request(Method::POST, "/customers/me/password", Some(body), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(body["errors"]["currentPassword"], json!(["current password does not match"]));
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_change_email_only_after_new_address_is_verified(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let new_email = String::from("new.user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let body = json!({ "email": "  New.User@fiap.com.br ", "currentPassword": password });
+
+        let response = send(&ctx, build_request(Method::POST, "/customers/me/email", Some(body), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(ctx.mail_sender.last_message_to(&email).is_some());
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &email, &password).await);
+        let verification = ctx.mail_sender.last_message_to(&new_email).expect("Expected a mail to the new address");
+        let verify = Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/verify-email"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "token": token_in(&verification.body) }).to_string()))
+            .expect("Failed to build request");
+        assert_eq!(StatusCode::NO_CONTENT, send(&ctx, verify).await.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, sing_in_status(&ctx, &email, &password).await);
+        assert_eq!(StatusCode::OK, sing_in_status(&ctx, &new_email, &password).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_conflict_when_change_email_to_one_in_use(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let taken_email = String::from("taken@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        AuthCommons::craete_customer(&ctx, &taken_email, &password).await;
+        let tokens = AuthCommons::sing_in(&ctx, &email, &password).await;
+        let body = json!({ "email": taken_email, "currentPassword": password });
+
+        let response = send(&ctx, build_request(Method::POST, "/customers/me/email", Some(body), &tokens.access_token)).await;
+
+        assert_eq!(StatusCode::CONFLICT, response.status());
+        assert!(ctx.mail_sender.last_message_to(&taken_email).is_none());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_get_me_without_authorization(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(String::from("/customers/me"))
+            .body(Body::empty())
+            .expect("Failed to build request");
+
+        let response = send(&ctx, request).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        Ok(())
+    }
+}
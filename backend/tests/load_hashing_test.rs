@@ -0,0 +1,82 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::Router;
+    use login_auth_service::app::AppRoutes;
+    use login_auth_service::support::hash::hashing_pool::HashingPool;
+    use rstest::rstest;
+    use serde_json::json;
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use crate::commons::{AuthCommons, TestContext};
+
+    const SING_IN_REQUESTS: usize = 16;
+
+    fn build_sing_in_request(email: &String, password: &String) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(String::from("/auth/singin"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "email": email, "password": password }).to_string()))
+            .expect("Failed to build request")
+    }
+
+    async fn health_latency(app: &Router) -> Duration {
+        let request = Request::builder()
+            .uri(String::from("/health"))
+            .body(Body::empty())
+            .expect("Failed to build request");
+        let started_at = Instant::now();
+        let response = app.clone().oneshot(request).await.expect("Failed to call health");
+        assert_eq!(StatusCode::OK, response.status());
+        started_at.elapsed()
+    }
+
+    /// Runs on a single threaded runtime on purpose: hashing inline would freeze `/health` entirely.
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_keep_health_latency_flat_while_hashing_pool_is_saturated(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("user@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(&ctx, &email, &password).await;
+        let mut app_state = ctx.app_state.clone();
+        app_state.hashing_pool = HashingPool::new(1, Duration::from_millis(20));
+        let app = AppRoutes::routes(app_state).await?;
+        let idle_latency = health_latency(&app).await;
+
+        let sing_ins = (0..SING_IN_REQUESTS)
+            .map(|_| {
+                let request = build_sing_in_request(&email, &password);
+                tokio::spawn(app.clone().oneshot(request))
+            })
+            .collect::<Vec<_>>();
+        let mut loaded_latency = Duration::ZERO;
+        while sing_ins.iter().any(|sing_in| !sing_in.is_finished()) {
+            loaded_latency = loaded_latency.max(health_latency(&app).await);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let mut statuses = Vec::new();
+        for sing_in in sing_ins {
+            statuses.push(sing_in.await??.status());
+        }
+
+        assert!(statuses.contains(&StatusCode::OK));
+        assert!(statuses.contains(&StatusCode::SERVICE_UNAVAILABLE));
+        assert!(statuses.iter().all(|status| *status == StatusCode::OK || *status == StatusCode::SERVICE_UNAVAILABLE));
+        assert!(
+            loaded_latency < idle_latency + Duration::from_millis(100),
+            "health took {:?} under load and {:?} idle", loaded_latency, idle_latency
+        );
+        Ok(())
+    }
+}
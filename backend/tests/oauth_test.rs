@@ -0,0 +1,551 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::response::Response;
+    use data_encoding::BASE64;
+    use login_auth_service::feature::auth::domain::Role;
+    use login_auth_service::support::jwt::{AuthorizationClaims, ClientAccessClaims, IdTokenClaims, Jwt};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use test_context::test_context;
+    use tower::ServiceExt;
+    use url::{form_urlencoded, Url};
+    use crate::commons::{body_as_json_value, AuthCommons, TestContext};
+
+    const REDIRECT_URI: &str = "https://app.fiap.com.br/callback";
+    const LOGIN_URL: &str = "http://localhost:8080/login";
+    // BASE64URL(SHA256(CODE_VERIFIER)), without padding.
+    const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mJ92ZoB9vn0zYwY5Wf0NFMcsBkxxtI";
+    const CODE_CHALLENGE: &str = "1vYC03VURXNGXRexGli5AvzuHYoPbqzDKGomXRaLRS0";
+
+    struct Client {
+        id: String,
+        secret: Option<String>,
+    }
+
+    async fn send(ctx: &&mut TestContext, request: Request<Body>) -> Response {
+        ctx.app.clone().oneshot(request).await.expect("Failed to send request")
+    }
+
+    async fn register_client(ctx: &&mut TestContext, body: Value) -> Response {
+        let admin_token = AuthCommons::admin_access_token(ctx).await;
+        let response = send(ctx, Request::builder()
+            .method(Method::POST)
+            .uri("/admin/oauth-clients")
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request")).await;
+        response
+    }
+
+    async fn create_client(ctx: &&mut TestContext, grant_types: Value) -> Client {
+        let body = json!({
+            "name": "Fiap web",
+            "redirectUris": [REDIRECT_URI],
+            "grantTypes": grant_types,
+            "scopes": ["openid", "email", "profile"],
+        });
+        let response = register_client(ctx, body).await;
+        assert_eq!(StatusCode::CREATED, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        Client {
+            id: body["clientId"].as_str().expect("Failed to read client id").to_string(),
+            secret: body["clientSecret"].as_str().map(String::from),
+        }
+    }
+
+    async fn sign_in(ctx: &&mut TestContext, email: &str) -> String {
+        let email = String::from(email);
+        let password = String::from("my$ecr3T");
+        AuthCommons::craete_customer(ctx, &email, &password).await;
+        AuthCommons::sing_in(ctx, &email, &password).await.access_token
+    }
+
+    fn authorize_request(params: &[(&str, &str)]) -> Request<Body> {
+        let query = form_urlencoded::Serializer::new(String::new()).extend_pairs(params).finish();
+        Request::builder()
+            .method(Method::GET)
+            .uri(format!("/oauth/authorize?{}", query))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn approve_request(access_token: &str, params: &[(String, String)]) -> Request<Body> {
+        let body = params.iter().map(|(name, value)| (name.clone(), json!(value))).collect::<serde_json::Map<String, Value>>();
+        Request::builder()
+            .method(Method::POST)
+            .uri("/oauth/authorize")
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .body(Body::from(Value::Object(body).to_string()))
+            .expect("Failed to build request")
+    }
+
+    fn token_request(client: &Client, params: &[(&str, &str)]) -> Request<Body> {
+        form_request(client, "/oauth/token", params)
+    }
+
+    fn form_request(client: &Client, uri: &str, params: &[(&str, &str)]) -> Request<Body> {
+        let body = form_urlencoded::Serializer::new(String::new()).extend_pairs(params).finish();
+        let credentials = format!("{}:{}", client.id, client.secret.clone().unwrap_or_default());
+        Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Authorization", format!("Basic {}", BASE64.encode(credentials.as_bytes())))
+            .body(Body::from(body))
+            .expect("Failed to build request")
+    }
+
+    fn bearer_request(method: Method, uri: &str, access_token: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .body(Body::empty())
+            .expect("Failed to build request")
+    }
+
+    fn redirect_params(response: &Response) -> Vec<(String, String)> {
+        let location = response.headers()[header::LOCATION].to_str().expect("Failed to read location");
+        assert!(location.starts_with(REDIRECT_URI));
+        url_params(location)
+    }
+
+    fn url_params(url: &str) -> Vec<(String, String)> {
+        let url = Url::parse(url).expect("Failed to parse url");
+        url.query_pairs().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    fn param(params: &[(String, String)], name: &str) -> Option<String> {
+        params.iter().find(|(param, _)| param == name).map(|(_, value)| value.clone())
+    }
+
+    /// Follows the browser to the login page, which approves the request with the customer access token.
+    async fn authorization_code(ctx: &&mut TestContext, client: &Client, access_token: &str, scope: &str) -> String {
+        let response = send(ctx, authorize_request(&[
+            ("response_type", "code"),
+            ("client_id", &client.id),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", scope),
+            ("state", "xyz"),
+            ("nonce", "n-0S6"),
+            ("code_challenge", CODE_CHALLENGE),
+            ("code_challenge_method", "S256"),
+        ])).await;
+        assert_eq!(StatusCode::FOUND, response.status());
+        let location = response.headers()[header::LOCATION].to_str().expect("Failed to read location");
+        assert!(location.starts_with(LOGIN_URL));
+        let login_params = url_params(location);
+        let response = send(ctx, approve_request(access_token, &login_params)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        let redirect_to = body["redirectTo"].as_str().expect("Failed to read redirect");
+        assert!(redirect_to.starts_with(REDIRECT_URI));
+        let params = url_params(redirect_to);
+        assert_eq!(Some(String::from("xyz")), param(&params, "state"));
+        param(&params, "code").expect("Failed to read code")
+    }
+
+    async fn issue_tokens(ctx: &&mut TestContext, client: &Client, email: &str, scope: &str) -> Value {
+        let access_token = sign_in(ctx, email).await;
+        let code = authorization_code(ctx, client, &access_token, scope).await;
+        let response = send(ctx, exchange_code(client, &code, CODE_VERIFIER)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        body_as_json_value(response.into_body()).await
+    }
+
+    async fn introspect(ctx: &&mut TestContext, client: &Client, token: &str) -> Value {
+        let response = send(ctx, form_request(client, "/oauth/introspect", &[("token", token)])).await;
+        assert_eq!(StatusCode::OK, response.status());
+        body_as_json_value(response.into_body()).await
+    }
+
+    fn exchange_code(client: &Client, code: &str, code_verifier: &str) -> Request<Body> {
+        token_request(client, &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", REDIRECT_URI),
+            ("code_verifier", code_verifier),
+        ])
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_publish_openid_configuration(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = send(&ctx, Request::builder()
+            .uri("/.well-known/openid-configuration")
+            .body(Body::empty())
+            .expect("Failed to build request")).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(json!("http://localhost:8080"), body["issuer"]);
+        assert_eq!(json!("http://localhost:8080/oauth/token"), body["token_endpoint"]);
+        assert_eq!(json!("http://localhost:8080/.well-known/jwks.json"), body["jwks_uri"]);
+        assert_eq!(json!("http://localhost:8080/oauth/introspect"), body["introspection_endpoint"]);
+        assert_eq!(json!("http://localhost:8080/oauth/revoke"), body["revocation_endpoint"]);
+        assert_eq!(json!(["S256"]), body["code_challenge_methods_supported"]);
+        assert_eq!(json!(["authorization_code", "refresh_token", "client_credentials"]), body["grant_types_supported"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_issue_tokens_for_authorization_code_with_pkce(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code", "refresh_token"])).await;
+        let access_token = sign_in(&ctx, "user@fiap.com.br").await;
+        let code = authorization_code(&ctx, &client, &access_token, "openid email").await;
+
+        let response = send(&ctx, exchange_code(&client, &code, CODE_VERIFIER)).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("no-store", response.headers()[header::CACHE_CONTROL]);
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(json!("Bearer"), body["token_type"]);
+        assert_eq!(json!("openid email"), body["scope"]);
+        assert!(body["refresh_token"].is_string());
+        let key_ring = &ctx.app_state.jwt_key_ring;
+        let claims = AuthorizationClaims::extract_jwt(body["access_token"].as_str().expect("Failed to read access token").to_string(), key_ring).expect("Failed to extract claims");
+        let id_token = IdTokenClaims::extract_jwt(body["id_token"].as_str().expect("Failed to read id token").to_string(), key_ring).expect("Failed to extract id token");
+        assert_eq!(Some(String::from("openid email")), claims.scope);
+        assert_eq!(client.id, claims.client_id.expect("Failed to read client id").to_string());
+        assert_eq!(client.id, id_token.aud);
+        assert_eq!(claims.sub, id_token.sub);
+        assert_eq!(Some(String::from("n-0S6")), id_token.nonce);
+        assert_eq!(Some(String::from("user@fiap.com.br")), id_token.email);
+        assert_eq!(None, id_token.name);
+        let userinfo = send(&ctx, bearer_request(Method::GET, "/oauth/userinfo", body["access_token"].as_str().expect("Failed to read access token"))).await;
+        assert_eq!(StatusCode::OK, userinfo.status());
+        let userinfo = body_as_json_value(userinfo.into_body()).await;
+        assert_eq!(json!(claims.sub.to_string()), userinfo["sub"]);
+        assert_eq!(json!("user@fiap.com.br"), userinfo["email"]);
+        assert!(userinfo.get("name").is_none());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_refuse_client_access_token_on_first_party_routes(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let email = String::from("admin@fiap.com.br");
+        let password = String::from("my$ecr3T");
+        let admin = AuthCommons::craete_customer(&ctx, &email, &password).await;
+        AuthCommons::grant_roles(&ctx, &admin, &[Role::Customer, Role::Admin]).await;
+        let first_party_token = AuthCommons::sing_in(&ctx, &email, &password).await.access_token;
+        let client = create_client(&ctx, json!(["authorization_code"])).await;
+        let code = authorization_code(&ctx, &client, &first_party_token, "openid").await;
+        let response = send(&ctx, exchange_code(&client, &code, CODE_VERIFIER)).await;
+        let body = body_as_json_value(response.into_body()).await;
+        let access_token = body["access_token"].as_str().expect("Failed to read access token");
+
+        let claims = AuthorizationClaims::extract_jwt(access_token.to_string(), &ctx.app_state.jwt_key_ring).expect("Failed to extract claims");
+        assert!(claims.roles.is_empty());
+        for uri in ["/customers/me", "/auth/sessions", "/admin/oauth-clients", &format!("/biometrics/actions/get/{}", admin.id)] {
+            let response = send(&ctx, bearer_request(Method::GET, uri, access_token)).await;
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status(), "{}", uri);
+        }
+        assert_eq!(StatusCode::OK, send(&ctx, bearer_request(Method::GET, "/oauth/userinfo", access_token)).await.status());
+        assert_eq!(StatusCode::OK, send(&ctx, bearer_request(Method::GET, "/admin/oauth-clients", &first_party_token)).await.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_session_when_authorization_code_is_replayed(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code"])).await;
+        let access_token = sign_in(&ctx, "user@fiap.com.br").await;
+        let code = authorization_code(&ctx, &client, &access_token, "openid").await;
+        let first = send(&ctx, exchange_code(&client, &code, CODE_VERIFIER)).await;
+        let first = body_as_json_value(first.into_body()).await;
+
+        let replayed = send(&ctx, exchange_code(&client, &code, CODE_VERIFIER)).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, replayed.status());
+        let body = body_as_json_value(replayed.into_body()).await;
+        assert_eq!(json!("invalid_grant"), body["error"]);
+        assert_eq!(json!("Authorization code already used"), body["error_description"]);
+        assert!(first.get("refresh_token").is_none());
+        let userinfo = send(&ctx, bearer_request(Method::GET, "/oauth/userinfo", first["access_token"].as_str().expect("Failed to read access token"))).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, userinfo.status());
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_reject_authorization_requests_the_oauth_way(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code"])).await;
+        let access_token = sign_in(&ctx, "user@fiap.com.br").await;
+
+        let unknown_redirect = send(&ctx, authorize_request(&[
+            ("response_type", "code"),
+            ("client_id", &client.id),
+            ("redirect_uri", "https://evil.com/callback"),
+            ("code_challenge", CODE_CHALLENGE),
+            ("code_challenge_method", "S256"),
+        ])).await;
+        let without_pkce = send(&ctx, authorize_request(&[
+            ("response_type", "code"),
+            ("client_id", &client.id),
+            ("redirect_uri", REDIRECT_URI),
+            ("state", "xyz"),
+        ])).await;
+        let unknown_scope = send(&ctx, authorize_request(&[
+            ("response_type", "code"),
+            ("client_id", &client.id),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", "openid admin"),
+            ("code_challenge", CODE_CHALLENGE),
+            ("code_challenge_method", "S256"),
+        ])).await;
+        let unauthenticated = send(&ctx, approve_request("", &[
+            (String::from("response_type"), String::from("code")),
+            (String::from("client_id"), client.id.clone()),
+            (String::from("redirect_uri"), String::from(REDIRECT_URI)),
+        ])).await;
+        let code = authorization_code(&ctx, &client, &access_token, "openid").await;
+        let wrong_verifier = send(&ctx, exchange_code(&client, &code, "not-the-verifier-used-for-the-challenge-at-all")).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, unknown_redirect.status());
+        assert_eq!(json!("invalid_request"), body_as_json_value(unknown_redirect.into_body()).await["error"]);
+        assert_eq!(StatusCode::FOUND, without_pkce.status());
+        let params = redirect_params(&without_pkce);
+        assert_eq!(Some(String::from("invalid_request")), param(&params, "error"));
+        assert_eq!(Some(String::from("xyz")), param(&params, "state"));
+        assert_eq!(Some(String::from("invalid_scope")), param(&redirect_params(&unknown_scope), "error"));
+        assert_eq!(StatusCode::UNAUTHORIZED, unauthenticated.status());
+        assert_eq!(StatusCode::BAD_REQUEST, wrong_verifier.status());
+        assert_eq!(json!("invalid_grant"), body_as_json_value(wrong_verifier.into_body()).await["error"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_rotate_refresh_token_only_for_the_client_it_was_issued_to(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code", "refresh_token"])).await;
+        let other_client = create_client(&ctx, json!(["authorization_code", "refresh_token"])).await;
+        let access_token = sign_in(&ctx, "user@fiap.com.br").await;
+        let code = authorization_code(&ctx, &client, &access_token, "openid email profile").await;
+        let tokens = body_as_json_value(send(&ctx, exchange_code(&client, &code, CODE_VERIFIER)).await.into_body()).await;
+        let refresh_token = tokens["refresh_token"].as_str().expect("Failed to read refresh token");
+
+        let by_other_client = send(&ctx, token_request(&other_client, &[("grant_type", "refresh_token"), ("refresh_token", refresh_token)])).await;
+        let by_auth_refresh = send(&ctx, Request::builder()
+            .method(Method::POST)
+            .uri("/auth/refresh")
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "refreshToken": refresh_token }).to_string()))
+            .expect("Failed to build request")).await;
+        let widened = send(&ctx, token_request(&client, &[("grant_type", "refresh_token"), ("refresh_token", refresh_token), ("scope", "openid phone")])).await;
+        let rotated = send(&ctx, token_request(&client, &[("grant_type", "refresh_token"), ("refresh_token", refresh_token), ("scope", "openid")])).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, by_other_client.status());
+        assert_eq!(json!("invalid_grant"), body_as_json_value(by_other_client.into_body()).await["error"]);
+        assert_eq!(StatusCode::UNAUTHORIZED, by_auth_refresh.status());
+        assert_eq!(json!("invalid_scope"), body_as_json_value(widened.into_body()).await["error"]);
+        assert_eq!(StatusCode::OK, rotated.status());
+        let rotated = body_as_json_value(rotated.into_body()).await;
+        assert_eq!(json!("openid"), rotated["scope"]);
+        assert_ne!(tokens["refresh_token"], rotated["refresh_token"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_issue_client_token_for_client_credentials(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["client_credentials"])).await;
+        let wrong_secret = Client { id: client.id.clone(), secret: Some(String::from("wrong")) };
+
+        let response = send(&ctx, token_request(&client, &[("grant_type", "client_credentials"), ("scope", "email")])).await;
+        let unauthenticated = send(&ctx, token_request(&wrong_secret, &[("grant_type", "client_credentials")])).await;
+        let not_allowed = send(&ctx, token_request(&client, &[("grant_type", "authorization_code"), ("code", "code")])).await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert!(body.get("refresh_token").is_none());
+        assert!(body.get("id_token").is_none());
+        let access_token = body["access_token"].as_str().expect("Failed to read access token").to_string();
+        let claims = ClientAccessClaims::extract_jwt(access_token.clone(), &ctx.app_state.jwt_key_ring).expect("Failed to extract claims");
+        assert_eq!(client.id, claims.sub.to_string());
+        assert_eq!("email", claims.scope);
+        assert_eq!(StatusCode::UNAUTHORIZED, send(&ctx, bearer_request(Method::GET, "/oauth/userinfo", &access_token)).await.status());
+        assert_eq!(StatusCode::UNAUTHORIZED, unauthenticated.status());
+        assert_eq!(json!("invalid_client"), body_as_json_value(unauthenticated.into_body()).await["error"]);
+        assert_eq!(json!("unauthorized_client"), body_as_json_value(not_allowed.into_body()).await["error"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_error_when_client_registration_is_invalid(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let body = json!({
+            "name": "Fiap mobile",
+            "redirectUris": ["/callback"],
+            "grantTypes": ["authorization_code", "client_credentials"],
+            "scopes": ["openid", "admin"],
+            "public": true,
+        });
+
+        let response = register_client(&ctx, body).await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = body_as_json_value(response.into_body()).await;
+        assert_eq!(json!("invalid fields: grantTypes, redirectUris, scopes"), body["message"]);
+        assert_eq!(json!(["admin is not supported"]), body["errors"]["scopes"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_introspect_tokens_until_they_are_revoked(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code", "refresh_token"])).await;
+        let tokens = issue_tokens(&ctx, &client, "introspect@fiap.com.br", "openid email").await;
+        let access_token = tokens["access_token"].as_str().expect("Failed to read access token");
+        let refresh_token = tokens["refresh_token"].as_str().expect("Failed to read refresh token");
+        let claims = AuthorizationClaims::extract_jwt(access_token.to_string(), &ctx.app_state.jwt_key_ring).expect("Failed to extract claims");
+
+        let active = introspect(&ctx, &client, access_token).await;
+        let refresh = introspect(&ctx, &client, refresh_token).await;
+        let unknown = introspect(&ctx, &client, "not-a-token").await;
+        let revoked = send(&ctx, form_request(&client, "/oauth/revoke", &[("token", access_token), ("token_type_hint", "access_token")])).await;
+        let inactive = introspect(&ctx, &client, access_token).await;
+
+        assert_eq!(json!(true), active["active"]);
+        assert_eq!(json!("openid email"), active["scope"]);
+        assert_eq!(json!(client.id), active["client_id"]);
+        assert_eq!(json!("Bearer"), active["token_type"]);
+        assert_eq!(json!(claims.sub), active["sub"]);
+        assert_eq!(json!(claims.exp), active["exp"]);
+        assert_eq!(json!(true), refresh["active"]);
+        assert_eq!(json!("refresh_token"), refresh["token_type"]);
+        assert_eq!(json!(claims.sub), refresh["sub"]);
+        assert_eq!(json!({"active": false}), unknown);
+        assert_eq!(StatusCode::OK, revoked.status());
+        assert_eq!(json!({"active": false}), inactive);
+        assert_eq!(StatusCode::UNAUTHORIZED, send(&ctx, bearer_request(Method::GET, "/oauth/userinfo", access_token)).await.status());
+        assert_eq!(json!(true), introspect(&ctx, &client, refresh_token).await["active"]);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_end_session_when_refresh_token_is_revoked(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["authorization_code", "refresh_token"])).await;
+        let tokens = issue_tokens(&ctx, &client, "revoke@fiap.com.br", "openid").await;
+        let access_token = tokens["access_token"].as_str().expect("Failed to read access token");
+        let refresh_token = tokens["refresh_token"].as_str().expect("Failed to read refresh token");
+
+        let revoked = send(&ctx, form_request(&client, "/oauth/revoke", &[("token", refresh_token)])).await;
+        let revoked_again = send(&ctx, form_request(&client, "/oauth/revoke", &[("token", refresh_token)])).await;
+        let refreshed = send(&ctx, token_request(&client, &[("grant_type", "refresh_token"), ("refresh_token", refresh_token)])).await;
+
+        assert_eq!(StatusCode::OK, revoked.status());
+        assert_eq!(StatusCode::OK, revoked_again.status());
+        assert_eq!(json!("invalid_grant"), body_as_json_value(refreshed.into_body()).await["error"]);
+        assert_eq!(json!({"active": false}), introspect(&ctx, &client, refresh_token).await);
+        assert_eq!(json!({"active": false}), introspect(&ctx, &client, access_token).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_revoke_only_tokens_issued_to_the_calling_client(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = create_client(&ctx, json!(["client_credentials"])).await;
+        let other_client = create_client(&ctx, json!(["client_credentials"])).await;
+        let wrong_secret = Client { id: client.id.clone(), secret: Some(String::from("wrong")) };
+        let response = send(&ctx, token_request(&client, &[("grant_type", "client_credentials"), ("scope", "email")])).await;
+        let body = body_as_json_value(response.into_body()).await;
+        let access_token = body["access_token"].as_str().expect("Failed to read access token");
+
+        let introspected_by_other = introspect(&ctx, &other_client, access_token).await;
+        let unauthenticated = send(&ctx, form_request(&wrong_secret, "/oauth/introspect", &[("token", access_token)])).await;
+        let revoked_by_other = send(&ctx, form_request(&other_client, "/oauth/revoke", &[("token", access_token)])).await;
+        let without_token = send(&ctx, form_request(&client, "/oauth/revoke", &[])).await;
+        let revoked = send(&ctx, form_request(&client, "/oauth/revoke", &[("token", access_token)])).await;
+
+        assert_eq!(json!(true), introspected_by_other["active"]);
+        assert_eq!(json!(client.id), introspected_by_other["client_id"]);
+        assert_eq!(json!(client.id), introspected_by_other["sub"]);
+        assert_eq!(json!("email"), introspected_by_other["scope"]);
+        assert_eq!(StatusCode::UNAUTHORIZED, unauthenticated.status());
+        assert_eq!(json!("invalid_client"), body_as_json_value(unauthenticated.into_body()).await["error"]);
+        assert_eq!(StatusCode::BAD_REQUEST, revoked_by_other.status());
+        assert_eq!(json!("unauthorized_client"), body_as_json_value(revoked_by_other.into_body()).await["error"]);
+        assert_eq!(json!("invalid_request"), body_as_json_value(without_token.into_body()).await["error"]);
+        assert_eq!(StatusCode::OK, revoked.status());
+        assert_eq!(json!({"active": false}), introspect(&ctx, &client, access_token).await);
+        Ok(())
+    }
+
+    #[test_context(TestContext)]
+    #[rstest]
+    #[tokio::test]
+    #[serial]
+    async fn should_return_unauthorized_when_public_client_introspects(
+        ctx: &mut TestContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = register_client(&ctx, json!({
+            "name": "Fiap mobile",
+            "redirectUris": [REDIRECT_URI],
+            "grantTypes": ["authorization_code"],
+            "public": true,
+        })).await;
+        let body = body_as_json_value(response.into_body()).await;
+        let public_client = Client { id: body["clientId"].as_str().expect("Failed to read client id").to_string(), secret: None };
+        let access_token = sign_in(&ctx, "public@fiap.com.br").await;
+
+        let response = send(&ctx, form_request(&public_client, "/oauth/introspect", &[("token", &access_token)])).await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!(json!("invalid_client"), body_as_json_value(response.into_body()).await["error"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+mod commons;
+
+#[cfg(test)]
+mod test {
+    use login_auth_service::support::sms::{http::HttpSmsSender, SmsMessage, SmsSender};
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+    use crate::commons::create_mock_server;
+
+    fn message() -> SmsMessage {
+        SmsMessage {
+            to: String::from("+5511987654321"),
+            body: String::from("Your sign in code is 123456."),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_post_message_to_sms_provider() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_server = create_mock_server().await;
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .and(header("Authorization", "Bearer provider-token"))
+            .and(body_json(json!({ "to": "+5511987654321", "message": "Your sign in code is 123456." })))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let sender = HttpSmsSender::new(format!("{}/messages", mock_server.uri()), String::from("provider-token"), 5)
+            .expect("Failed to create sender");
+
+        let result = sender.send(message()).await;
+
+        assert!(result.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_return_error_when_sms_provider_fails() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_server = create_mock_server().await;
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+        let sender = HttpSmsSender::new(format!("{}/messages", mock_server.uri()), String::from("provider-token"), 5)
+            .expect("Failed to create sender");
+
+        let result = sender.send(message()).await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            "Business(AppErrorData { status: 502, message: \"SMS provider answered with status 500\", tags: None, errors: None, oauth_error: None, headers: {} })",
+            format!("{:?}", result.err().unwrap())
+        );
+        Ok(())
+    }
+}